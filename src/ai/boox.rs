@@ -16,10 +16,7 @@ impl AI {
 
 impl Player for AI {
     fn name(&self) -> String {
-        match self.id {
-            PlayerId::One => "Boox One".to_string(),
-            PlayerId::Two => "Boox Two".to_string(),
-        }
+        format!("Boox {}", self.id)
     }
 
     fn play(&self, board: Board) -> Edge {
@@ -30,7 +27,7 @@ impl Player for AI {
             .collect::<Vec<Edge>>()
             .iter()
             .rev()
-            .map(|&edge| edge)
+            .copied()
             .flat_map(|edge| {
                 board
                     .associated_boxes(edge)
@@ -41,10 +38,10 @@ impl Player for AI {
             .filter(|&edge| board.is_free(edge))
             .partition(|&edge| board.would_claim_box(edge));
 
-        if claimers.len() > 0 {
+        if !claimers.is_empty() {
             return claimers[0];
         }
-        if others.len() > 0 {
+        if !others.is_empty() {
             return others[0];
         }
         board
@@ -1,5 +1,7 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use crate::game::board::{Board, Edge};
-use crate::players::player::{Player, PlayerId};
+use crate::players::player::{Player, PlayerId, TurnContext};
 
 pub const KEY: &str = "doot";
 
@@ -16,16 +18,31 @@ impl AI {
 
 impl Player for AI {
     fn name(&self) -> String {
-        match self.id {
-            PlayerId::One => "Doot One".to_string(),
-            PlayerId::Two => "Doot Two".to_string(),
-        }
+        format!("Doot {}", self.id)
     }
 
     fn play(&self, board: Board) -> Edge {
+        self.play_until(board, &AtomicBool::new(false))
+    }
+
+    fn play_turn(&self, context: TurnContext) -> Edge {
+        self.play_until(context.board, &context.cancelled)
+    }
+}
+
+impl AI {
+    /*
+     * Checking every edge takes a while on a big board, so this settles for
+     * the best edge so far once it's cancelled.
+     */
+    fn play_until(&self, board: Board, cancelled: &AtomicBool) -> Edge {
+        let mut board = board;
         let mut scored_edges: Vec<(i8, Edge)> = vec![];
 
         for edge in board.iter_edges() {
+            if !scored_edges.is_empty() && cancelled.load(Ordering::Relaxed) {
+                break;
+            }
             if !board.is_free(edge) {
                 continue;
             }
@@ -37,13 +54,11 @@ impl Player for AI {
             }
 
             // Check if drawing the edge would open up a box for the opponent.
-            let mut hypothetical_board = board.clone();
-            hypothetical_board
-                .draw((self.id, edge))
-                .expect("Invalid draw");
-            let gives_to_opponent = hypothetical_board
+            board.draw((self.id, edge)).expect("Invalid draw");
+            let gives_to_opponent = board
                 .iter_edges()
-                .any(|opponent_edge| hypothetical_board.would_claim_box(opponent_edge));
+                .any(|opponent_edge| board.would_claim_box(opponent_edge));
+            board.undo();
             if gives_to_opponent {
                 score -= 1
             }
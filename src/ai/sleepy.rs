@@ -1,10 +1,11 @@
-use std::thread;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
-use chrono::{Datelike, Local, Weekday};
+use chrono::{Datelike, Weekday};
 
 use crate::game::board::{edge, Board, Edge};
-use crate::players::player::{Player, PlayerId};
+use crate::game::clock::{Clock, SystemClock};
+use crate::players::player::{Player, PlayerId, TurnContext};
 
 pub const KEY: &str = "sleepy";
 
@@ -21,19 +22,26 @@ impl AI {
 
 impl Player for AI {
     fn name(&self) -> String {
-        match self.id {
-            PlayerId::One => "Sleepy One".to_string(),
-            PlayerId::Two => "Sleepy Two".to_string(),
-        }
+        format!("Sleepy {}", self.id)
+    }
+
+    fn play(&self, board: Board) -> Edge {
+        self.play_on_clock(board, &SystemClock, &AtomicBool::new(false))
+    }
+
+    fn play_turn(&self, context: TurnContext) -> Edge {
+        self.play_on_clock(context.board, context.clock.as_ref(), &context.cancelled)
     }
+}
 
+impl AI {
     /*
      * Just for fun, this AI sleeps before making a move. Cute, but annoying
-     * to test as you have to wait for it to finish. Also, you would have to
-     * mock/modify the system clock to test the "special" behaviors.
+     * to test, which is why it keeps time with whatever clock it's given. It
+     * does wake up when told its time is up.
      */
-    fn play(&self, board: Board) -> Edge {
-        let weekday = Local::today().weekday();
+    fn play_on_clock(&self, board: Board, clock: &dyn Clock, cancelled: &AtomicBool) -> Edge {
+        let weekday = clock.now().weekday();
         if weekday == Weekday::Mon {
             panic!("I HATE MONDAYS!");
         }
@@ -45,8 +53,11 @@ impl Player for AI {
             1
         };
         for _ in 0..sleep_secs {
+            if cancelled.load(Ordering::Relaxed) {
+                break;
+            }
             println!("snore... ");
-            thread::sleep(Duration::from_secs(1));
+            clock.sleep(Duration::from_secs(1));
         }
 
         // And it's not even good at playing the game...
@@ -55,6 +66,6 @@ impl Player for AI {
                 return edge;
             }
         }
-        return edge((0, 0), (0, 1));
+        edge((0, 0), (0, 1))
     }
 }
@@ -1,4 +1,18 @@
+pub mod analysis;
 pub mod basic_types;
+pub mod batch;
 pub mod board;
 pub mod box_drawings;
+pub mod clock;
+pub mod coins;
 pub mod engine;
+pub mod error;
+pub mod notation;
+pub mod observer;
+pub mod opening;
+pub mod ratings;
+pub mod record;
+pub mod replay;
+pub mod symmetry;
+pub mod tournament;
+pub mod zobrist;
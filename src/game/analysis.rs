@@ -0,0 +1,201 @@
+/*
+ * Structure of the undrawn part of a board, as used by dots and boxes
+ * strategy: boxes that can be claimed now, chains and loops of boxes that
+ * will be claimed together, and the moves that don't give anything away.
+ *
+ * The "valence" of a box is how many of its edges are still undrawn.
+ */
+use std::collections::HashSet;
+
+use crate::game::board::{Board, DotBox, Edge};
+
+mod nimstring;
+
+pub use nimstring::{component_nimbers, nimber, ComponentNimber, Nimber, NimberCache};
+
+/* Where one end of a chain leads. */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChainEnd {
+    /* An undrawn edge on the border of the board. */
+    Ground(Edge),
+    /* A box with three or four undrawn edges, where chains meet. */
+    Joint(DotBox),
+    /* A box with one undrawn edge, so the chain is open for the taking. */
+    Capturable(DotBox),
+}
+
+/*
+ * A run of boxes with exactly two undrawn edges each, in order from the first
+ * end to the second. Drawing any edge of a chain hands the whole thing over.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Chain {
+    pub boxes: Vec<DotBox>,
+    pub ends: (ChainEnd, ChainEnd),
+}
+
+impl Chain {
+    pub fn length(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /* Whether either end can already be claimed. */
+    pub fn is_open(&self) -> bool {
+        let (first, second) = self.ends;
+        matches!(first, ChainEnd::Capturable(_)) || matches!(second, ChainEnd::Capturable(_))
+    }
+}
+
+/*
+ * A chain that closes on itself, in order around the loop. The anchor is the
+ * joint both ends meet at, or None for a loop made only of chain boxes.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loop {
+    pub boxes: Vec<DotBox>,
+    pub anchor: Option<DotBox>,
+}
+
+impl Loop {
+    pub fn length(&self) -> usize {
+        self.boxes.len()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    pub chains: Vec<Chain>,
+    pub loops: Vec<Loop>,
+    /* Boxes with one undrawn edge, which the player to move can claim. */
+    pub capturable: Vec<DotBox>,
+    /* How many undrawn edges can be drawn without offering a box. */
+    pub safe_moves: usize,
+}
+
+pub fn analyze(board: &Board) -> Analysis {
+    let mut chains: Vec<Chain> = vec![];
+    let mut loops: Vec<Loop> = vec![];
+    let mut visited: HashSet<DotBox> = HashSet::new();
+
+    for start in board.iter_boxes() {
+        if valence(board, start) != 2 || visited.contains(&start) {
+            continue;
+        }
+        visited.insert(start);
+
+        let start_edges = free_edges(board, start);
+        let (forward, forward_end) = walk(board, start, start_edges[0], &mut visited);
+        let forward_end = match forward_end {
+            Some(end) => end,
+            None => {
+                let mut boxes = vec![start];
+                boxes.extend(forward);
+                loops.push(Loop {
+                    boxes,
+                    anchor: None,
+                });
+                continue;
+            }
+        };
+        // Walks only come back around to the start for loops, which are
+        // handled above, so the backward walk always finds an end.
+        let (backward, backward_end) = walk(board, start, start_edges[1], &mut visited);
+        let backward_end = backward_end.unwrap();
+
+        let mut boxes: Vec<DotBox> = backward.into_iter().rev().collect();
+        boxes.push(start);
+        boxes.extend(forward);
+
+        match (backward_end, forward_end) {
+            (ChainEnd::Joint(a), ChainEnd::Joint(b)) if a == b => loops.push(Loop {
+                boxes,
+                anchor: Some(a),
+            }),
+            ends => chains.push(Chain { boxes, ends }),
+        }
+    }
+
+    Analysis {
+        chains,
+        loops,
+        capturable: board
+            .iter_boxes()
+            .filter(|&dotbox| valence(board, dotbox) == 1)
+            .collect(),
+        safe_moves: safe_edges(board).len(),
+    }
+}
+
+/*
+ * Undrawn edges that don't leave any box with a single undrawn edge, so the
+ * next player gets nothing from them.
+ */
+pub fn safe_edges(board: &Board) -> Vec<Edge> {
+    board
+        .iter_edges()
+        .filter(|&edge| board.is_free(edge))
+        .filter(|&edge| {
+            board
+                .associated_boxes(edge)
+                .iter()
+                .all(|&dotbox| 3 <= valence(board, dotbox))
+        })
+        .collect()
+}
+
+/* How many edges of the box are still undrawn. */
+pub fn valence(board: &Board, dotbox: DotBox) -> u8 {
+    4 - board.box_edge_count(dotbox)
+}
+
+fn free_edges(board: &Board, dotbox: DotBox) -> Vec<Edge> {
+    dotbox
+        .edges()
+        .into_iter()
+        .filter(|&edge| board.is_free(edge))
+        .collect()
+}
+
+/*
+ * Follows a chain away from the start box, through the given undrawn edge,
+ * marking each chain box as visited. Returns the boxes passed through (not
+ * including the start) and where the chain ended, or None if it came back
+ * around to the start.
+ */
+fn walk(
+    board: &Board,
+    start: DotBox,
+    first_edge: Edge,
+    visited: &mut HashSet<DotBox>,
+) -> (Vec<DotBox>, Option<ChainEnd>) {
+    let mut path: Vec<DotBox> = vec![];
+    let mut current = start;
+    let mut through = first_edge;
+
+    loop {
+        let next = match board
+            .associated_boxes(through)
+            .into_iter()
+            .find(|&dotbox| dotbox != current)
+        {
+            Some(next) => next,
+            None => return (path, Some(ChainEnd::Ground(through))),
+        };
+        if next == start {
+            return (path, None);
+        }
+        match valence(board, next) {
+            1 => return (path, Some(ChainEnd::Capturable(next))),
+            2 => {
+                visited.insert(next);
+                path.push(next);
+                through = free_edges(board, next)
+                    .into_iter()
+                    .find(|&edge| edge != through)
+                    .unwrap();
+                current = next;
+            }
+            _ => return (path, Some(ChainEnd::Joint(next))),
+        }
+    }
+}
@@ -0,0 +1,284 @@
+/*
+ * Nimstring values of positions, following Berlekamp's "The Dots and Boxes
+ * Game". Nimstring is dots and boxes where the only goal is to make the last
+ * move (a player who claims a box still has to move again), and its values
+ * tell a player which side of the chain-counting fight they are on and which
+ * pieces of the board to give away first.
+ *
+ * Positions are worked out on the strings and coins graph. A position where
+ * the player to move has been offered at least two boxes in a chain is
+ * "loony": that player can take them all or decline the last two, so they
+ * win whatever else is on the board. Moves into loony positions are never
+ * counted as options.
+ */
+use std::collections::HashMap;
+
+use crate::game::board::{dot, Board, DotBox};
+use crate::game::coins::Node;
+use crate::game::symmetry::Symmetry;
+
+type CoinString = (Node, Node);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Nimber {
+    Value(usize),
+    Loony,
+}
+
+/* The value of one independent piece of the board, by the coins in it. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentNimber {
+    pub coins: Vec<DotBox>,
+    pub nimber: Nimber,
+}
+
+/*
+ * Remembers the values of components it has already worked out, keyed by
+ * their canonical form so that components that are shifted, turned or
+ * flipped copies of each other share one entry. Reuse a cache across
+ * positions from the same game to avoid repeating work.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct NimberCache {
+    values: HashMap<Vec<CoinString>, Nimber>,
+}
+
+/* The Nimstring value of the whole board. */
+pub fn nimber(board: &Board) -> Nimber {
+    NimberCache::new().nimber(board)
+}
+
+/* The Nimstring values of each independent piece of the board. */
+pub fn component_nimbers(board: &Board) -> Vec<ComponentNimber> {
+    NimberCache::new().component_nimbers(board)
+}
+
+impl NimberCache {
+    pub fn new() -> NimberCache {
+        Self::default()
+    }
+
+    pub fn nimber(&mut self, board: &Board) -> Nimber {
+        self.value(board_strings(board))
+    }
+
+    pub fn component_nimbers(&mut self, board: &Board) -> Vec<ComponentNimber> {
+        components(&board_strings(board))
+            .into_iter()
+            .map(|component| ComponentNimber {
+                coins: coins_of(&component),
+                nimber: self.value(component),
+            })
+            .collect()
+    }
+
+    /* How many component values are remembered. */
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn value(&mut self, strings: Vec<CoinString>) -> Nimber {
+        let strings = match capture(strings) {
+            Some(strings) => strings,
+            None => return Nimber::Loony,
+        };
+        let mut total = 0;
+        for component in components(&strings) {
+            match self.component_value(component) {
+                Nimber::Value(value) => total ^= value,
+                Nimber::Loony => return Nimber::Loony,
+            }
+        }
+        Nimber::Value(total)
+    }
+
+    /* The value of a connected component with nothing left to capture. */
+    fn component_value(&mut self, strings: Vec<CoinString>) -> Nimber {
+        let key = canonical(&strings);
+        if let Some(&known) = self.values.get(&key) {
+            return known;
+        }
+
+        let mut options: Vec<usize> = vec![];
+        for cut in 0..strings.len() {
+            let mut rest = strings.clone();
+            rest.remove(cut);
+            if let Nimber::Value(value) = self.value(rest) {
+                options.push(value);
+            }
+        }
+        let mex = (0..).find(|value| !options.contains(value)).unwrap();
+
+        let nimber = Nimber::Value(mex);
+        self.values.insert(key, nimber);
+        nimber
+    }
+}
+
+fn board_strings(board: &Board) -> Vec<CoinString> {
+    board
+        .to_coins_graph()
+        .strings()
+        .iter()
+        .map(|string| string.ends)
+        .collect()
+}
+
+fn degree(strings: &[CoinString], node: Node) -> usize {
+    strings
+        .iter()
+        .filter(|&&(a, b)| a == node || b == node)
+        .count()
+}
+
+fn other_end((a, b): CoinString, node: Node) -> Node {
+    if a == node {
+        b
+    } else {
+        a
+    }
+}
+
+/*
+ * Claims every coin that can be claimed without giving anything up, which
+ * leaves the value unchanged because the same player keeps moving. Returns
+ * None if what remains is loony instead.
+ */
+fn capture(mut strings: Vec<CoinString>) -> Option<Vec<CoinString>> {
+    loop {
+        let mut offered_chain = false;
+        let mut safe_capture: Option<usize> = None;
+
+        for (index, &(a, b)) in strings.iter().enumerate() {
+            for &coin in [a, b].iter() {
+                if coin == Node::Ground || degree(&strings, coin) != 1 {
+                    continue;
+                }
+                let neighbor = other_end((a, b), coin);
+                if neighbor == Node::Ground || degree(&strings, neighbor) != 2 {
+                    safe_capture = Some(index);
+                    break;
+                }
+                // The neighbor is the next box of a chain. The capture is only
+                // forced if the chain ends in another capturable coin, since
+                // otherwise the last two coins can be declined.
+                let (beyond_index, _) = strings
+                    .iter()
+                    .enumerate()
+                    .find(|&(i, &string)| {
+                        i != index && (string.0 == neighbor || string.1 == neighbor)
+                    })
+                    .unwrap();
+                let beyond = other_end(strings[beyond_index], neighbor);
+                if beyond != Node::Ground && degree(&strings, beyond) == 1 {
+                    safe_capture = Some(index);
+                    break;
+                }
+                offered_chain = true;
+            }
+            if safe_capture.is_some() {
+                break;
+            }
+        }
+
+        match safe_capture {
+            Some(index) => {
+                strings.remove(index);
+            }
+            None if offered_chain => return None,
+            None => return Some(strings),
+        }
+    }
+}
+
+/* Splits strings into pieces that only touch each other through the ground. */
+fn components(strings: &[CoinString]) -> Vec<Vec<CoinString>> {
+    let mut remaining: Vec<CoinString> = strings.to_vec();
+    let mut found: Vec<Vec<CoinString>> = vec![];
+
+    while let Some(first) = remaining.pop() {
+        let mut component = vec![first];
+        let mut frontier: Vec<Node> = vec![first.0, first.1];
+        while let Some(node) = frontier.pop() {
+            if node == Node::Ground {
+                continue;
+            }
+            let (attached, detached): (Vec<CoinString>, Vec<CoinString>) = remaining
+                .into_iter()
+                .partition(|&(a, b)| a == node || b == node);
+            remaining = detached;
+            for string in attached {
+                frontier.push(other_end(string, node));
+                component.push(string);
+            }
+        }
+        found.push(component);
+    }
+
+    found
+}
+
+fn coins_of(strings: &[CoinString]) -> Vec<DotBox> {
+    let mut coins: Vec<DotBox> = strings
+        .iter()
+        .flat_map(|&(a, b)| vec![a, b])
+        .filter_map(|node| match node {
+            Node::Coin(coin) => Some(coin),
+            Node::Ground => None,
+        })
+        .collect();
+    coins.sort();
+    coins.dedup();
+    coins
+}
+
+/*
+ * A key shared by every shifted, turned or flipped copy of a component: the
+ * smallest sorted string list over the symmetries of its bounding box, after
+ * moving it to the upper-left corner. A box taller than it is wide is
+ * transposed first, since a non-square box has no symmetries that turn it.
+ */
+fn canonical(strings: &[CoinString]) -> Vec<CoinString> {
+    let coins = coins_of(strings);
+    if coins.is_empty() {
+        return vec![];
+    }
+    let min_row = coins.iter().map(|c| c.0.row).min().unwrap();
+    let min_col = coins.iter().map(|c| c.0.col).min().unwrap();
+    let rows = coins.iter().map(|c| c.0.row).max().unwrap() - min_row + 1;
+    let cols = coins.iter().map(|c| c.0.col).max().unwrap() - min_col + 1;
+    let transpose = rows > cols;
+    let (rows, cols) = if transpose {
+        (cols, rows)
+    } else {
+        (rows, cols)
+    };
+
+    Symmetry::for_dimensions(rows, cols)
+        .into_iter()
+        .map(|symmetry| {
+            let place = |node: Node| match node {
+                Node::Coin(DotBox(corner)) => {
+                    let (row, col) = (corner.row - min_row, corner.col - min_col);
+                    let (row, col) = if transpose { (col, row) } else { (row, col) };
+                    Node::Coin(symmetry.transform_box(DotBox(dot(row, col)), rows, cols))
+                }
+                Node::Ground => Node::Ground,
+            };
+            let mut placed: Vec<CoinString> = strings
+                .iter()
+                .map(|&(a, b)| {
+                    let (a, b) = (place(a), place(b));
+                    (a.min(b), a.max(b))
+                })
+                .collect();
+            placed.sort();
+            placed
+        })
+        .min()
+        .unwrap()
+}
@@ -1,5 +1,6 @@
 use core::fmt;
 use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::ops;
 
 use crate::game::box_drawings::{DOT, LINE_H};
@@ -31,7 +32,7 @@ pub struct Dot {
 
 impl Dot {
     pub fn transpose(&self) -> Self {
-        dot(self.row, self.col)
+        dot(self.col, self.row)
     }
 }
 
@@ -47,6 +48,13 @@ impl PartialEq for Dot {
     }
 }
 
+impl Hash for Dot {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.row.hash(state);
+        self.col.hash(state);
+    }
+}
+
 impl Ord for Dot {
     fn cmp(&self, other: &Self) -> Ordering {
         match self.row.cmp(&other.row) {
@@ -74,7 +82,7 @@ impl ops::Sub for Dot {
 }
 
 fn abs_sub(a: BoardSize, b: BoardSize) -> BoardSize {
-    ((a as i8) - (b as i8)).abs() as BoardSize
+    a.abs_diff(b)
 }
 
 impl ops::Add for Dot {
@@ -119,6 +127,14 @@ impl PartialEq for Edge {
     }
 }
 
+/* Hashes the dots in sorted order to agree with the order-insensitive eq. */
+impl Hash for Edge {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.min(self.1).hash(state);
+        self.0.max(self.1).hash(state);
+    }
+}
+
 /*
  * Dot
  */
@@ -169,6 +185,24 @@ impl PartialEq for DotBox {
     }
 }
 
+impl Hash for DotBox {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.upper_left().hash(state);
+    }
+}
+
+impl Ord for DotBox {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.upper_left().cmp(&other.upper_left())
+    }
+}
+
+impl PartialOrd for DotBox {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /*
  * WinnerResult
  */
@@ -179,6 +213,26 @@ pub enum WinnerResult {
     None,
 }
 
+impl WinnerResult {
+    /* Whoever has the most boxes out of the given players and box counts. */
+    pub fn from_counts(counts: &[(PlayerId, usize)]) -> WinnerResult {
+        let winning_count = match counts.iter().map(|&(_, count)| count).max() {
+            Some(count) => count,
+            None => return WinnerResult::None,
+        };
+        let winners: Vec<PlayerId> = counts
+            .iter()
+            .filter(|&&(_, count)| count == winning_count)
+            .map(|&(id, _)| id)
+            .collect();
+        if winners.len() == 1 {
+            WinnerResult::Winner(winners[0], winning_count)
+        } else {
+            WinnerResult::Tie(winners, winning_count)
+        }
+    }
+}
+
 impl PartialEq for WinnerResult {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -0,0 +1,166 @@
+/*
+ * Plays lots of headless games at once, spread over a pool of threads. Each
+ * game gets its own players and its own seed worked out from the game's
+ * index, so the results don't depend on how many threads there are or which
+ * one finished first.
+ */
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use crate::game::board::WinnerResult;
+use crate::game::engine::{play_headless, GameOutcome, Opts};
+use crate::game::error::GameError;
+use crate::game::ratings::{outcome_scores, Ratings};
+use crate::players::choose::AI_KEYS;
+use crate::players::player::PlayerId;
+use crate::utils::{pad_end, splitmix64};
+
+/* The seed for the game with the given index, so any game can be replayed. */
+pub fn game_seed(seed: u64, game_index: usize) -> u64 {
+    splitmix64(seed ^ game_index as u64)
+}
+
+/*
+ * Calls `play` with every index from 0 up to `count` on `jobs` threads, and
+ * returns what it gave back in index order. `done` is called on this thread
+ * with each result, also in index order, as soon as the results before it
+ * are in.
+ */
+pub fn play_in_parallel<T, F, D>(count: usize, jobs: usize, play: F, mut done: D) -> Vec<T>
+where
+    T: Send,
+    F: Fn(usize) -> T + Sync,
+    D: FnMut(usize, &T),
+{
+    let mut results: Vec<Option<T>> = (0..count).map(|_| None).collect();
+    let next_index = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..jobs.clamp(1, count.max(1)) {
+            let sender = sender.clone();
+            let next_index = &next_index;
+            let play = &play;
+            scope.spawn(move || loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                if count <= index || sender.send((index, play(index))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut reported = 0;
+        for (index, result) in receiver {
+            results[index] = Some(result);
+            while let Some(Some(result)) = results.get(reported) {
+                done(reported, result);
+                reported += 1;
+            }
+        }
+    });
+
+    results.into_iter().map(Option::unwrap).collect()
+}
+
+/*
+ * Plays `games` games of the same setup on `jobs` threads, every one with a
+ * seed from game_seed. The ratings file, if the options have one, is updated
+ * in the order the games were numbered. Only AIs can play in a batch.
+ */
+pub fn play_batch(opts: &Opts, games: usize, jobs: usize) -> Result<Vec<GameOutcome>, GameError> {
+    if let Some(player) = opts
+        .players
+        .iter()
+        .find(|player| !AI_KEYS.contains(&player.as_str()))
+    {
+        return Err(GameError::Setup(format!(
+            "Player {} can't play in a batch, try one of: {}",
+            player,
+            AI_KEYS.join(", ")
+        )));
+    }
+    let mut ratings = match &opts.ratings {
+        Some(path) => Some(Ratings::load(path)?),
+        None => None,
+    };
+
+    let outcomes = play_in_parallel(
+        games,
+        jobs,
+        |index| {
+            // The ratings are updated below in game order, not as games finish.
+            play_headless(&Opts {
+                seed: game_seed(opts.seed, index),
+                ratings: None,
+                ..opts.clone()
+            })
+        },
+        |_, played| {
+            if let (Some(ratings), Ok(outcome)) = (ratings.as_mut(), played) {
+                if let Some(scores) = outcome_scores(&opts.players, outcome) {
+                    ratings.record_game(&scores);
+                }
+            }
+        },
+    );
+
+    if let (Some(path), Some(ratings)) = (&opts.ratings, &ratings) {
+        ratings.save(path)?;
+    }
+    outcomes.into_iter().collect()
+}
+
+/*
+ * Sums up a batch for each seat: wins, ties, forfeits and average boxes.
+ *
+ *   Player      Wins  Ties  Forfeits  Average boxes
+ *   1 doot      97    1     0         6.42
+ *   2 boox      2     1     0         2.58
+ */
+pub fn summarize(players: &[String], outcomes: &[GameOutcome]) -> String {
+    let mut lines = vec![format!(
+        "{}{}{}{}Average boxes",
+        pad_end("Player", " ", 12),
+        pad_end("Wins", " ", 6),
+        pad_end("Ties", " ", 6),
+        pad_end("Forfeits", " ", 10)
+    )];
+    for (index, player) in players.iter().enumerate() {
+        let seat = index + 1;
+        let in_seat = |id: &PlayerId| id.seat() == seat;
+        let wins = outcomes
+            .iter()
+            .filter(|o| matches!(&o.result, WinnerResult::Winner(id, _) if in_seat(id)))
+            .count();
+        let ties = outcomes
+            .iter()
+            .filter(|o| matches!(&o.result, WinnerResult::Tie(ids, _) if ids.iter().any(in_seat)))
+            .count();
+        let forfeits = outcomes
+            .iter()
+            .filter(|o| o.forfeit.as_ref().is_some_and(|f| in_seat(&f.player_id)))
+            .count();
+        let boxes: usize = outcomes
+            .iter()
+            .flat_map(|o| o.box_counts.iter())
+            .filter(|(id, _)| in_seat(id))
+            .map(|&(_, count)| count)
+            .sum();
+        let average = if outcomes.is_empty() {
+            0.0
+        } else {
+            boxes as f64 / outcomes.len() as f64
+        };
+        lines.push(format!(
+            "{}{}{}{}{:.2}",
+            pad_end(&format!("{} {}", seat, player), " ", 12),
+            pad_end(&wins.to_string(), " ", 6),
+            pad_end(&ties.to_string(), " ", 6),
+            pad_end(&forfeits.to_string(), " ", 10),
+            average
+        ));
+    }
+    lines.join("\n")
+}
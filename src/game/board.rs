@@ -1,60 +1,157 @@
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 
 pub use crate::game::basic_types::{dot, dotbox, edge, BoardSize, Dot, DotBox, Edge, WinnerResult};
-use crate::game::box_drawings::{lookup, BoxChar, LINE_H, LINE_V};
+use crate::game::box_drawings::{lookup, lookup_value, BoxChar, LINE_H, LINE_V};
+use crate::game::coins::CoinsGraph;
+use crate::game::error::DrawError;
+use crate::game::symmetry::Symmetry;
+use crate::game::zobrist;
 use crate::players::player::PlayerId;
 use crate::utils::{pad_end, pad_out};
 
 pub type OwnedEdge = (PlayerId, Edge);
 
+/* How many characters wide each dot is in the drawing. */
+const CELL_WIDTH: usize = 3;
+
+/* The smallest and largest boards doots plays on, in boxes per side. */
+pub const MIN_SIZE: BoardSize = 1;
+pub const MAX_SIZE: BoardSize = 100;
+
+/*
+ * Everything that matters about a position for the rest of the game: the
+ * shape of the board, which edges are drawn and who owns each completed box.
+ * Unlike the Board itself, it knows nothing about the order of the draws or
+ * who drew which edge.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PositionKey {
+    pub rows: BoardSize,
+    pub cols: BoardSize,
+    /* One bit per edge_index, set when the edge is drawn. */
+    pub drawn: Vec<u64>,
+    /* One entry per box_index. */
+    pub box_owners: Vec<Option<PlayerId>>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Board {
-    size: BoardSize,
+    /* The number of boxes tall the board is. */
+    rows: BoardSize,
+    /* The number of boxes wide the board is. */
+    cols: BoardSize,
     /* Edges are kept in the order they were drawn. */
     owned_edges: Vec<OwnedEdge>,
+    /* Edges taken back by undo, most recently undone last. */
+    undone_edges: Vec<OwnedEdge>,
+    /* Owner of every edge on the board, indexed by edge_index. */
+    edge_owners: Vec<Option<PlayerId>>,
+    /* How many of the four edges of each box are drawn, indexed by box_index. */
+    box_edge_counts: Vec<u8>,
+    /* Owner of every completed box, indexed by box_index. */
+    box_owners: Vec<Option<PlayerId>>,
+    /* Zobrist hash of the drawn edges and box owners, see game::zobrist. */
+    zobrist: u64,
     player_ids: Vec<PlayerId>,
 }
 
+/*
+ * Boards are equal when their positions are, regardless of the order or owner
+ * of the draws that got them there.
+ */
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.zobrist == other.zobrist && self.position_key() == other.position_key()
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.zobrist.hash(state);
+    }
+}
+
 impl Default for Board {
     fn default() -> Self {
-        Self {
-            size: 2,
-            owned_edges: vec![],
-            player_ids: vec![PlayerId::One, PlayerId::Two],
-        }
+        Self::new(2)
     }
 }
 
 impl Board {
+    /* A square board that is `size` boxes wide and tall. */
     pub fn new(size: BoardSize) -> Board {
-        Board {
-            size,
-            ..Default::default()
-        }
+        Self::with_dimensions(size, size)
     }
 
-    pub fn size(&self) -> BoardSize {
-        self.size
+    pub fn with_dimensions(rows: BoardSize, cols: BoardSize) -> Board {
+        let mut board = Board {
+            rows,
+            cols,
+            owned_edges: vec![],
+            undone_edges: vec![],
+            edge_owners: vec![],
+            box_edge_counts: vec![],
+            box_owners: vec![],
+            zobrist: zobrist::dimensions_key(rows, cols),
+            player_ids: vec![PlayerId::One, PlayerId::Two],
+        };
+        board.edge_owners = vec![None; board.edge_count() as usize];
+        board.box_edge_counts = vec![0; board.box_count()];
+        board.box_owners = vec![None; board.box_count()];
+        board
     }
 
-    /* The number of dots in a row (equal to column) */
-    pub fn dot_size(&self) -> BoardSize {
-        self.size + 1
+    /*
+     * Sets which players are in the game. Every player appears in the results
+     * of owner_to_boxes and winner, even if they haven't claimed any boxes.
+     */
+    pub fn with_player_ids(mut self, player_ids: Vec<PlayerId>) -> Board {
+        self.player_ids = player_ids;
+        self
+    }
+
+    pub fn player_ids(&self) -> &[PlayerId] {
+        &self.player_ids
+    }
+
+    /* The number of boxes in a column */
+    pub fn rows(&self) -> BoardSize {
+        self.rows
+    }
+
+    /* The number of boxes in a row */
+    pub fn cols(&self) -> BoardSize {
+        self.cols
+    }
+
+    /* The number of dots in a column */
+    pub fn dot_rows(&self) -> BoardSize {
+        self.rows + 1
+    }
+
+    /* The number of dots in a row */
+    pub fn dot_cols(&self) -> BoardSize {
+        self.cols + 1
     }
 
     /* The number of dots in the whole board */
     pub fn dot_count(&self) -> BoardSize {
-        let (sq, is_over) = (self.dot_size()).overflowing_pow(2);
-        if is_over {
-            // As if this will ever happened:
-            BoardSize::MAX
-        } else {
-            sq
-        }
+        // As if this will ever overflow:
+        self.dot_rows()
+            .checked_mul(self.dot_cols())
+            .unwrap_or(BoardSize::MAX)
     }
 
+    /* Horizontal edges plus vertical edges. */
     pub fn edge_count(&self) -> BoardSize {
-        2 * self.size * self.dot_size()
+        self.dot_rows() * self.cols + self.rows * self.dot_cols()
+    }
+
+    pub fn box_count(&self) -> usize {
+        (self.rows as usize) * (self.cols as usize)
     }
 
     /*
@@ -64,49 +161,201 @@ impl Board {
         (self.edge_count() as usize) <= self.owned_edges.len()
     }
 
-    pub fn draw(&mut self, (owner, edge): OwnedEdge) -> Result<Edge, String> {
-        let validation = self.validate_draw(edge);
-        if validation.is_ok() {
-            self.owned_edges.push((owner, edge));
-        }
-        validation
+    pub fn draw(&mut self, owned_edge: OwnedEdge) -> Result<Edge, DrawError> {
+        let edge = self.validate_draw(owned_edge.1)?;
+        self.apply_draw(owned_edge);
+        self.undone_edges.clear();
+        Ok(edge)
     }
 
-    pub fn draw_many(&mut self, owned_edges: Vec<OwnedEdge>) -> Result<BoardSize, String> {
-        let mut success_count = 0;
+    /*
+     * Draws all of the edges or none of them. Every edge is validated before
+     * anything is drawn, including against the other edges in the batch.
+     */
+    pub fn draw_many(&mut self, owned_edges: Vec<OwnedEdge>) -> Result<BoardSize, DrawError> {
+        let mut batch_indexes: Vec<usize> = vec![];
         for &(_, edge) in &owned_edges {
-            match self.validate_draw(edge) {
-                Ok(_) => success_count += 1,
-                Err(msg) => return Err(msg),
-            };
+            self.validate_draw(edge)?;
+            let index = self.edge_index(edge).unwrap();
+            if batch_indexes.contains(&index) {
+                return Err(DrawError::AlreadyDrawn(edge));
+            }
+            batch_indexes.push(index);
         }
         for &owned_edge in &owned_edges {
-            self.owned_edges.push(owned_edge);
+            self.apply_draw(owned_edge);
         }
-        Ok(success_count)
+        self.undone_edges.clear();
+        Ok(owned_edges.len() as BoardSize)
     }
 
-    pub fn validate_draw(&self, edge: Edge) -> Result<Edge, String> {
+    /*
+     * Takes back the most recently drawn edge, returning it if there was one.
+     * Undone edges can be drawn again with redo until something new is drawn.
+     */
+    pub fn undo(&mut self) -> Option<OwnedEdge> {
+        let owned_edge = self.owned_edges.pop()?;
+        let (_, edge) = owned_edge;
+        let index = self.edge_index(edge).unwrap();
+        self.edge_owners[index] = None;
+        self.zobrist ^= zobrist::edge_key(index);
+        for dotbox in self.associated_boxes(edge) {
+            let box_index = self.box_index(dotbox).unwrap();
+            self.box_edge_counts[box_index] -= 1;
+            if let Some(box_owner) = self.box_owners[box_index].take() {
+                self.zobrist ^= zobrist::box_key(box_index, box_owner);
+            }
+        }
+        self.undone_edges.push(owned_edge);
+        Some(owned_edge)
+    }
+
+    /* Draws the most recently undone edge again, returning it if there was one. */
+    pub fn redo(&mut self) -> Option<OwnedEdge> {
+        let owned_edge = self.undone_edges.pop()?;
+        self.apply_draw(owned_edge);
+        Some(owned_edge)
+    }
+
+    /*
+     * Undoes edges until only the first `turn` edges remain drawn. The undone
+     * edges can be brought back with redo.
+     */
+    pub fn truncate_to(&mut self, turn: usize) {
+        while turn < self.owned_edges.len() {
+            self.undo();
+        }
+    }
+
+    pub fn validate_draw(&self, edge: Edge) -> Result<Edge, DrawError> {
         if !edge.is_valid() {
-            return Err(format!("Cannot draw invalid edge: {}", edge));
+            return Err(DrawError::InvalidEdge(edge));
         } else if !self.edge_fits(edge) {
-            return Err(format!(
-                "Edge {:?} does not fit in board of size {}",
-                edge, self.size
-            ));
+            return Err(DrawError::DoesNotFit {
+                edge,
+                rows: self.rows,
+                cols: self.cols,
+            });
         } else if self.is_drawn(edge) {
-            return Err(format!("Cannot redraw edge: {}", edge));
+            return Err(DrawError::AlreadyDrawn(edge));
         }
         Ok(edge)
     }
 
-    pub fn is_free(&self, Edge(d1, d2): Edge) -> bool {
-        for &connected in self.dots_connected_to_dot(d1).iter() {
-            if d2 == connected {
-                return false;
+    /*
+     * Records an already validated edge in the draw history and in the
+     * edge/box indexes.
+     */
+    fn apply_draw(&mut self, (owner, edge): OwnedEdge) {
+        let index = self.edge_index(edge).unwrap();
+        self.edge_owners[index] = Some(owner);
+        self.zobrist ^= zobrist::edge_key(index);
+        for dotbox in self.associated_boxes(edge) {
+            let box_index = self.box_index(dotbox).unwrap();
+            self.box_edge_counts[box_index] += 1;
+            if self.box_edge_counts[box_index] == 4 {
+                self.box_owners[box_index] = Some(owner);
+                self.zobrist ^= zobrist::box_key(box_index, owner);
             }
         }
-        true
+        self.owned_edges.push((owner, edge));
+    }
+
+    /*
+     * Zobrist hash of the position, kept up to date by every draw and undo.
+     * Equal positions always have equal hashes, whatever order they were
+     * drawn in.
+     */
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    pub fn position_key(&self) -> PositionKey {
+        let mut drawn = vec![0u64; self.edge_owners.len().div_ceil(64)];
+        for (index, owner) in self.edge_owners.iter().enumerate() {
+            if owner.is_some() {
+                drawn[index / 64] |= 1 << (index % 64);
+            }
+        }
+        PositionKey {
+            rows: self.rows,
+            cols: self.cols,
+            drawn,
+            box_owners: self.box_owners.clone(),
+        }
+    }
+
+    /*
+     * The same game turned or flipped by the symmetry: every edge is redrawn
+     * by the same owner in the same order.
+     */
+    pub fn transform(&self, symmetry: Symmetry) -> Board {
+        let (rows, cols) = symmetry.dimensions(self.rows, self.cols);
+        let mut transformed =
+            Board::with_dimensions(rows, cols).with_player_ids(self.player_ids.clone());
+        for &(owner, edge) in self.owned_edges.iter() {
+            let edge = symmetry.transform_edge(edge, self.rows, self.cols);
+            transformed.apply_draw((owner, edge));
+        }
+        transformed
+    }
+
+    /*
+     * The symmetry that turns this board into its canonical form: the
+     * equivalent position with the smallest position_key.
+     */
+    pub fn canonical_symmetry(&self) -> Symmetry {
+        Symmetry::for_dimensions(self.rows, self.cols)
+            .into_iter()
+            .min_by_key(|&symmetry| self.transform(symmetry).position_key())
+            .unwrap()
+    }
+
+    /*
+     * The equivalent position with the smallest position_key, so that
+     * symmetric positions all share one canonical board.
+     */
+    pub fn canonical(&self) -> Board {
+        self.transform(self.canonical_symmetry())
+    }
+
+    /* The strings and coins view of the undrawn part of the board. */
+    pub fn to_coins_graph(&self) -> CoinsGraph {
+        CoinsGraph::from_board(self)
+    }
+
+    /*
+     * A unique index for every edge that fits in the board. Horizontal edges
+     * come first, row by row, followed by the vertical edges.
+     */
+    pub fn edge_index(&self, edge: Edge) -> Option<usize> {
+        if !edge.is_valid() || !self.edge_fits(edge) {
+            return None;
+        }
+        let start = edge.0.min(edge.1);
+        let end = edge.0.max(edge.1);
+        let (rows, cols) = (self.rows as usize, self.cols as usize);
+        let (row, col) = (start.row as usize, start.col as usize);
+        if start.row == end.row {
+            Some(row * cols + col)
+        } else {
+            Some((rows + 1) * cols + row * (cols + 1) + col)
+        }
+    }
+
+    /* A unique index for every box in the board, in the order of iter_boxes. */
+    pub fn box_index(&self, DotBox(corner): DotBox) -> Option<usize> {
+        if self.rows <= corner.row || self.cols <= corner.col {
+            return None;
+        }
+        Some((corner.row as usize) * (self.cols as usize) + (corner.col as usize))
+    }
+
+    pub fn is_free(&self, edge: Edge) -> bool {
+        match self.edge_index(edge) {
+            Some(index) => self.edge_owners[index].is_none(),
+            None => true,
+        }
     }
 
     pub fn is_drawn(&self, edge: Edge) -> bool {
@@ -114,20 +363,27 @@ impl Board {
     }
 
     pub fn would_claim_box(&self, edge: Edge) -> bool {
-        for dotbox in self.associated_boxes(edge) {
-            let box_owned_edges = self.box_owned_edges(dotbox);
-            if box_owned_edges.len() == 3 && !box_owned_edges.iter().any(|&(_, e)| e == edge) {
-                return true;
-            }
-        }
+        self.is_free(edge)
+            && self
+                .associated_boxes(edge)
+                .iter()
+                .any(|&dotbox| self.box_edge_count(dotbox) == 3)
+    }
 
-        false
+    /* How many of the four edges of the box have been drawn. */
+    pub fn box_edge_count(&self, dotbox: DotBox) -> u8 {
+        match self.box_index(dotbox) {
+            Some(index) => self.box_edge_counts[index],
+            None => 0,
+        }
     }
 
+    /* The drawn edges of a box, in the order of DotBox::edges. */
     pub fn box_owned_edges(&self, dotbox: DotBox) -> Vec<OwnedEdge> {
-        self.edge_indexes(dotbox.edges())
+        dotbox
+            .edges()
             .iter()
-            .map(|&i| self.owned_edges[i])
+            .filter_map(|&edge| self.edge_owner(edge).map(|owner| (owner, edge)))
             .collect()
     }
 
@@ -151,7 +407,7 @@ impl Board {
             if 0 < start.col {
                 boxes.push(DotBox(start - dot(0, 1)));
             }
-            if start.col < self.size() {
+            if start.col < self.cols {
                 boxes.push(DotBox(start));
             }
         } else if start.col < end.col {
@@ -159,7 +415,7 @@ impl Board {
             if 0 < start.row {
                 boxes.push(DotBox(start - dot(1, 0)));
             }
-            if start.row < self.size() {
+            if start.row < self.rows {
                 boxes.push(DotBox(start));
             }
         }
@@ -167,42 +423,10 @@ impl Board {
         boxes
     }
 
-    /*
-     * Which PlayerId drew the edge. At the moment, the owner methods are all
-     * O(n) where n = number of edges drawn. This could be sped up by storing
-     * owner information at draw time.
-     */
+    /* Which PlayerId drew the edge. */
     pub fn edge_owner(&self, edge: Edge) -> Option<PlayerId> {
-        let indexes = self.edge_indexes(vec![edge]);
-        if indexes.len() < 1 {
-            return None;
-        }
-
-        self.edge_index_owner(indexes[0])
-    }
-
-    fn edge_index_owner(&self, edge_index: usize) -> Option<PlayerId> {
-        if self.owned_edges.len() <= edge_index {
-            return None;
-        }
-
-        Some(self.owned_edges[edge_index].0)
-    }
-
-    fn edge_indexes(&self, edges_to_find: Vec<Edge>) -> Vec<usize> {
-        let mut found: Vec<usize> = vec![];
-        for (i, &(_, drawn_edge)) in self.owned_edges.iter().enumerate() {
-            for &to_find in edges_to_find.iter() {
-                if drawn_edge == to_find {
-                    found.push(i)
-                }
-            }
-            if found.len() == edges_to_find.len() {
-                break;
-            }
-        }
-
-        found
+        self.edge_index(edge)
+            .and_then(|index| self.edge_owners[index])
     }
 
     /*
@@ -213,12 +437,8 @@ impl Board {
      *    or bottom edge.
      */
     pub fn box_owner(&self, corner: Dot) -> Option<PlayerId> {
-        let dot_box = DotBox(corner);
-        let indexes = self.edge_indexes(dot_box.edges());
-        if indexes.len() < 4 {
-            return None;
-        }
-        self.edge_index_owner(*indexes.last().unwrap())
+        self.box_index(DotBox(corner))
+            .and_then(|index| self.box_owners[index])
     }
 
     pub fn owner_to_boxes(&self) -> HashMap<PlayerId, Vec<DotBox>> {
@@ -227,19 +447,18 @@ impl Board {
             hash.insert(player_id, vec![]);
         }
         for dotbox in self.iter_boxes() {
-            match self.box_owner(dotbox.0) {
-                Some(owner_id) => hash.get_mut(&owner_id).unwrap().push(dotbox),
-                None => {}
+            if let Some(owner_id) = self.box_owner(dotbox.0) {
+                hash.get_mut(&owner_id).unwrap().push(dotbox);
             }
         }
         hash
     }
 
     pub fn owned_boxes_count(&self, owner: PlayerId) -> usize {
-        match self.owner_to_boxes().get(&owner) {
-            Some(boxes) => boxes.len(),
-            None => 0,
-        }
+        self.box_owners
+            .iter()
+            .filter(|&&box_owner| box_owner == Some(owner))
+            .count()
     }
 
     pub fn winner(&self) -> WinnerResult {
@@ -248,32 +467,18 @@ impl Board {
         }
 
         let counts: Vec<(PlayerId, usize)> = self
-            .owner_to_boxes()
+            .player_ids
             .iter()
-            .map(|(&id, boxes)| (id, boxes.len()))
+            .map(|&id| (id, self.owned_boxes_count(id)))
             .collect();
-
-        let winning_count = *counts.iter().map(|(_, count)| count).max().unwrap();
-        let mut winners: Vec<PlayerId> = vec![];
-        for &(id, count) in counts.iter() {
-            if count == winning_count {
-                winners.push(id);
-            }
-        }
-        if winners.len() == 0 {
-            return WinnerResult::None;
-        } else if winners.len() == 1 {
-            WinnerResult::Winner(winners[0], winning_count)
-        } else {
-            WinnerResult::Tie(winners, winning_count)
-        }
+        WinnerResult::from_counts(&counts)
     }
 
     /* Whether the dot fits in this board. */
     pub fn dot_fits(&self, Dot { row, col }: Dot) -> bool {
         // Note that comparison to zero is unnecessary due to
         // unsigned integer type.
-        row < self.dot_size() && col < self.dot_size()
+        row < self.dot_rows() && col < self.dot_cols()
     }
 
     /* Whether the edge fits in this board. */
@@ -283,8 +488,8 @@ impl Board {
 
     /* Iterate across all dots in order of left-to-right, top-to-botom. */
     pub fn iter_dots(&self) -> impl Iterator<Item = Dot> {
-        let size = self.dot_size();
-        (0..size).flat_map(move |row| (0..size).map(move |col| dot(row, col)))
+        let dot_cols = self.dot_cols();
+        (0..self.dot_rows()).flat_map(move |row| (0..dot_cols).map(move |col| dot(row, col)))
     }
 
     /* Iterate across all edges in the same order as iter_dots.
@@ -296,15 +501,15 @@ impl Board {
      * appear only as second entries of an edge.
      */
     pub fn iter_edges(&self) -> impl Iterator<Item = Edge> {
-        let max_dot_index = self.dot_size() - 1;
+        let (rows, cols) = (self.rows, self.cols);
         self.iter_dots().flat_map(move |d| {
             let mut d_edges = vec![];
             // Right
-            if d.col < max_dot_index {
+            if d.col < cols {
                 d_edges.push(Edge(d, d + dot(0, 1)));
             }
             // Down
-            if d.row < max_dot_index {
+            if d.row < rows {
                 d_edges.push(Edge(d, d + dot(1, 0)));
             }
 
@@ -316,26 +521,33 @@ impl Board {
      * Iterator over owned edges in the order they were drawn (most recent turn last).
      */
     pub fn iter_owned_edges(&self) -> impl Iterator<Item = OwnedEdge> + '_ {
-        self.owned_edges.iter().map(move |&oe| oe)
+        self.owned_edges.iter().copied()
     }
 
     pub fn iter_boxes(&self) -> impl Iterator<Item = DotBox> {
-        let max_dot_index = self.dot_size() - 1;
+        let (rows, cols) = (self.rows, self.cols);
         self.iter_dots()
             // Exlude right and bottom dots:
-            .filter(move |d| d.row < max_dot_index && d.col < max_dot_index)
+            .filter(move |d| d.row < rows && d.col < cols)
             // Wrap the valid dots into boxes:
-            .map(|d| DotBox(d))
+            .map(DotBox)
     }
 
-    /*
-     * O(n). Should be able to make this O(1) by mapping...
-     */
-    pub fn edges_connected_to_dot(&self, dot: Dot) -> Vec<Edge> {
-        self.owned_edges
+    /* The drawn edges touching a dot, in the order up, right, down, left. */
+    pub fn edges_connected_to_dot(&self, center: Dot) -> Vec<Edge> {
+        let mut neighbors: Vec<Dot> = vec![];
+        if 0 < center.row {
+            neighbors.push(center - dot(1, 0));
+        }
+        neighbors.push(center + dot(0, 1));
+        neighbors.push(center + dot(1, 0));
+        if 0 < center.col {
+            neighbors.push(center - dot(0, 1));
+        }
+        neighbors
             .iter()
-            .filter(|&(_, edge)| edge.has_dot(dot))
-            .map(|&(_, edge)| edge)
+            .map(|&neighbor| Edge(center, neighbor))
+            .filter(|&edge| self.is_drawn(edge))
             .collect()
     }
 
@@ -347,26 +559,27 @@ impl Board {
     }
 
     /*
-     * One chonky func!
+     * One chonky func! Draws the board with box drawing characters. Note that
+     * this is not the same as Display, which writes the compact notation from
+     * game::notation.
      */
-    pub fn to_string(&self) -> String {
-        let cell_width = 3;
-        let dot_size = self.dot_size();
+    pub fn drawing(&self) -> String {
+        let cell_width = CELL_WIDTH;
         let mut grid: Vec<String> = vec![];
 
         // Header guide row:
         let mut row_string = " ".repeat(cell_width);
-        for col in 0..dot_size {
+        for col in 0..self.dot_cols() {
             row_string.push_str(&pad_end(&col.to_string(), " ", cell_width));
         }
         grid.push(row_string);
 
-        for row in 0..dot_size {
+        for row in 0..self.dot_rows() {
             // Left guide column:
             let mut dot_row_string = pad_out(&row.to_string(), " ", cell_width);
             let mut fill_row_string = pad_out("", " ", cell_width);
 
-            for col in 0..dot_size {
+            for col in 0..self.dot_cols() {
                 let cell_dot = dot(row, col);
                 // Pick the appropriate box intersection:
                 let entry = self.choose_char(cell_dot);
@@ -398,6 +611,151 @@ impl Board {
         grid.join("\n")
     }
 
+    /*
+     * Rebuilds a board from the output of drawing, like a position copied
+     * out of a terminal. Trailing whitespace and blank lines may be trimmed.
+     *
+     * Drawings don't say who drew which edge or in what order, so the edges
+     * are replayed in an order that gives every marked box to its owner.
+     * Edges that don't finish a box are given to the first player.
+     */
+    pub fn from_drawing(drawing: &str) -> Result<Board, String> {
+        let mut lines = drawing
+            .lines()
+            .map(|line| line.trim_end().chars().collect::<Vec<char>>())
+            .filter(|line| !line.is_empty())
+            .peekable();
+        let char_at = |line: &Vec<char>, index: usize| *line.get(index).unwrap_or(&' ');
+
+        // Header guide row:
+        let header: String = lines.next().ok_or("The drawing is empty")?.iter().collect();
+        let dot_cols = header.split_whitespace().count();
+        for (col, guide) in header.split_whitespace().enumerate() {
+            if guide != col.to_string() {
+                return Err(format!("Column guide {} should be {}", guide, col));
+            }
+        }
+
+        // Left guide column, with the fill row below each dot row:
+        let is_dot_row = |line: &Vec<char>| {
+            let guide: String = line.iter().take(CELL_WIDTH).collect();
+            guide.trim().parse::<usize>().is_ok()
+        };
+        let mut rows: Vec<(Vec<char>, Vec<char>)> = vec![];
+        while let Some(dot_row) = lines.next() {
+            let guide: String = dot_row.iter().take(CELL_WIDTH).collect();
+            if guide.trim() != rows.len().to_string() {
+                return Err(format!(
+                    "Row guide {} should be {}",
+                    guide.trim(),
+                    rows.len()
+                ));
+            }
+            let fill_row = match lines.peek() {
+                Some(line) if !is_dot_row(line) => lines.next().unwrap(),
+                _ => vec![],
+            };
+            rows.push((dot_row, fill_row));
+        }
+        if dot_cols < 2 || rows.len() < 2 {
+            return Err("The drawing needs at least one box".to_string());
+        }
+
+        let mut board =
+            Board::with_dimensions((rows.len() - 1) as BoardSize, (dot_cols - 1) as BoardSize);
+        let mut drawn: Vec<Edge> = vec![];
+        let mut glyphs: Vec<(Dot, char)> = vec![];
+        let mut marks: HashMap<DotBox, PlayerId> = HashMap::new();
+        for (row, (dot_row, fill_row)) in rows.iter().enumerate() {
+            for col in 0..dot_cols {
+                let cell_dot = dot(row as BoardSize, col as BoardSize);
+                let glyph = char_at(dot_row, CELL_WIDTH * (col + 1));
+                let box_char = match glyph {
+                    ' ' => BoxChar::default(),
+                    _ => lookup_value(glyph)
+                        .ok_or(format!("Unexpected character {} at {}", glyph, cell_dot))?,
+                };
+                glyphs.push((cell_dot, glyph));
+                let right = Edge(cell_dot, cell_dot + dot(0, 1));
+                let down = Edge(cell_dot, cell_dot + dot(1, 0));
+                for &(is_drawn, edge) in [(box_char.right, right), (box_char.down, down)].iter() {
+                    if is_drawn {
+                        board.validate_draw(edge)?;
+                        drawn.push(edge);
+                    }
+                }
+
+                let mark = char_at(fill_row, CELL_WIDTH * (col + 1) + 1);
+                if mark != ' ' {
+                    let owner = mark
+                        .to_digit(10)
+                        .and_then(|seat| PlayerId::from_seat(seat as usize))
+                        .ok_or(format!("Unexpected owner {} at {}", mark, cell_dot))?;
+                    marks.insert(DotBox(cell_dot), owner);
+                }
+            }
+        }
+
+        let max_seat = marks.values().map(|owner| owner.seat()).max().unwrap_or(2);
+        board.player_ids = PlayerId::ALL[..max_seat.max(2)].to_vec();
+
+        for dotbox in board.iter_boxes() {
+            let complete = dotbox.edges().iter().all(|edge| drawn.contains(edge));
+            if complete != marks.contains_key(&dotbox) {
+                return Err(format!(
+                    "Box at {} is {} but {} an owner",
+                    dotbox.upper_left(),
+                    if complete { "complete" } else { "not complete" },
+                    if complete { "doesn't have" } else { "has" },
+                ));
+            }
+        }
+
+        // Work backwards from the finished drawing: any edge can be the last
+        // one drawn as long as the boxes it would finish share an owner.
+        // Taking an edge away only ever unfinishes boxes, so this never paints
+        // itself into a corner.
+        let mut remaining = drawn;
+        let mut reversed: Vec<OwnedEdge> = vec![];
+        while !remaining.is_empty() {
+            let mut found: Option<(usize, PlayerId)> = None;
+            for (index, &edge) in remaining.iter().enumerate() {
+                let finished_owners: Vec<PlayerId> = board
+                    .associated_boxes(edge)
+                    .iter()
+                    .filter(|dotbox| dotbox.edges().iter().all(|e| remaining.contains(e)))
+                    .map(|dotbox| marks[dotbox])
+                    .collect();
+                match finished_owners.as_slice() {
+                    [] => found = Some((index, board.player_ids[0])),
+                    [owner] => found = Some((index, *owner)),
+                    [a, b] if a == b => found = Some((index, *a)),
+                    _ => continue,
+                }
+                break;
+            }
+            let (index, owner) = found.ok_or("No order of moves gives every box to its owner")?;
+            reversed.push((owner, remaining.remove(index)));
+        }
+
+        for owned_edge in reversed.into_iter().rev() {
+            board.draw(owned_edge)?;
+        }
+
+        // Edges were only read from the right and down of each dot, so make
+        // sure the up and left sides agree.
+        for (cell_dot, glyph) in glyphs {
+            let expected = board.choose_char(cell_dot).value;
+            if glyph != ' ' && glyph != expected {
+                return Err(format!(
+                    "Character {} at {} doesn't match its neighbors, expected {}",
+                    glyph, cell_dot, expected
+                ));
+            }
+        }
+        Ok(board)
+    }
+
     pub fn choose_char(&self, dot: Dot) -> BoxChar {
         let mut box_char = BoxChar::default();
         for connected in self.dots_connected_to_dot(dot).iter() {
@@ -418,8 +776,7 @@ impl Board {
 
     pub fn choose_owner_char(&self, dot: Dot) -> char {
         match self.box_owner(dot) {
-            Some(PlayerId::One) => '1',
-            Some(PlayerId::Two) => '2',
+            Some(owner) => owner.glyph(),
             None => ' ',
         }
     }
@@ -126,6 +126,14 @@ pub const BOX_CHARS: [BoxChar; 16] = [
     },
 ];
 
+/* The box character drawn with this glyph, if any. */
+pub fn lookup_value(value: char) -> Option<BoxChar> {
+    BOX_CHARS
+        .iter()
+        .find(|target| target.value == value)
+        .copied()
+}
+
 pub fn lookup(query: BoxChar) -> BoxChar {
     for target in BOX_CHARS.iter() {
         if target.up == query.up
@@ -0,0 +1,68 @@
+/*
+ * Where players get the time from. The engine hands every player a clock in
+ * their TurnContext, so that tests can swap the system clock for a FakeClock
+ * and skip straight to Monday, or through a five second nap.
+ */
+use std::fmt;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDateTime};
+
+pub trait Clock: fmt::Debug + Send + Sync {
+    /* The local date and time. */
+    fn now(&self) -> NaiveDateTime;
+    fn sleep(&self, duration: Duration);
+}
+
+/* The real thing. */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> NaiveDateTime {
+        Local::now().naive_local()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/* A clock that only moves when it's told to, and sleeps without waiting. */
+#[derive(Debug)]
+pub struct FakeClock {
+    now: Mutex<NaiveDateTime>,
+    slept: Mutex<Duration>,
+}
+
+impl FakeClock {
+    pub fn new(now: NaiveDateTime) -> FakeClock {
+        FakeClock {
+            now: Mutex::new(now),
+            slept: Mutex::new(Duration::from_secs(0)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += chrono::Duration::from_std(duration).unwrap();
+    }
+
+    /* How long everyone has slept on this clock in total. */
+    pub fn slept(&self) -> Duration {
+        *self.slept.lock().unwrap()
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> NaiveDateTime {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        *self.slept.lock().unwrap() += duration;
+        self.advance(duration);
+    }
+}
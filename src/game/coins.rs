@@ -0,0 +1,152 @@
+/*
+ * The "strings and coins" dual of a board, which is what most of the
+ * combinatorial game theory for dots and boxes is written in terms of.
+ *
+ * Every unclaimed box is a coin and every undrawn edge is a string. Strings
+ * between two boxes tie those coins together, while strings on the border of
+ * the board tie their coin to the ground. Drawing an edge cuts its string, and
+ * a coin with no strings left has been claimed.
+ */
+use crate::game::board::{Board, DotBox, Edge};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Node {
+    Ground,
+    Coin(DotBox),
+}
+
+/* An undrawn edge and the two nodes it ties together. */
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CoinString {
+    pub edge: Edge,
+    pub ends: (Node, Node),
+}
+
+impl CoinString {
+    pub fn touches(&self, node: Node) -> bool {
+        self.ends.0 == node || self.ends.1 == node
+    }
+
+    /* The node on the other end from the given one. */
+    pub fn other_end(&self, node: Node) -> Node {
+        if self.ends.0 == node {
+            self.ends.1
+        } else {
+            self.ends.0
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoinsGraph {
+    /* Unclaimed boxes in the order of Board::iter_boxes. */
+    coins: Vec<DotBox>,
+    /* Undrawn edges in the order of Board::iter_edges. */
+    strings: Vec<CoinString>,
+}
+
+impl CoinsGraph {
+    pub fn from_board(board: &Board) -> CoinsGraph {
+        let coins = board
+            .iter_boxes()
+            .filter(|&dotbox| board.box_edge_count(dotbox) < 4)
+            .collect();
+        let strings = board
+            .iter_edges()
+            .filter(|&edge| board.is_free(edge))
+            .map(|edge| {
+                let boxes = board.associated_boxes(edge);
+                let ends = match boxes.as_slice() {
+                    [a, b] => (Node::Coin(*a), Node::Coin(*b)),
+                    [a] => (Node::Coin(*a), Node::Ground),
+                    _ => (Node::Ground, Node::Ground),
+                };
+                CoinString { edge, ends }
+            })
+            .collect();
+        CoinsGraph { coins, strings }
+    }
+
+    pub fn coins(&self) -> &[DotBox] {
+        &self.coins
+    }
+
+    pub fn strings(&self) -> &[CoinString] {
+        &self.strings
+    }
+
+    /* The number of strings attached to the node. */
+    pub fn degree(&self, node: Node) -> usize {
+        self.strings.iter().filter(|s| s.touches(node)).count()
+    }
+
+    /*
+     * The nodes tied to this one, once per string, so a coin with two strings
+     * to the ground lists the ground twice.
+     */
+    pub fn neighbors(&self, node: Node) -> Vec<Node> {
+        self.strings_at(node)
+            .iter()
+            .map(|s| s.other_end(node))
+            .collect()
+    }
+
+    pub fn strings_at(&self, node: Node) -> Vec<CoinString> {
+        self.strings
+            .iter()
+            .filter(|s| s.touches(node))
+            .copied()
+            .collect()
+    }
+
+    /* The string for an undrawn edge, or None if the edge is drawn. */
+    pub fn string_for_edge(&self, edge: Edge) -> Option<CoinString> {
+        self.strings.iter().find(|s| s.edge == edge).copied()
+    }
+
+    /* The edges whose strings tie the two nodes together. */
+    pub fn edges_between(&self, a: Node, b: Node) -> Vec<Edge> {
+        self.strings
+            .iter()
+            .filter(|s| s.ends == (a, b) || s.ends == (b, a))
+            .map(|s| s.edge)
+            .collect()
+    }
+
+    /*
+     * Graphviz source for the graph. Coins are pinned to their place on the
+     * board, so render it with `neato` to keep the layout.
+     */
+    pub fn to_graphviz(&self) -> String {
+        let mut lines: Vec<String> = vec!["graph coins {".to_string()];
+        lines.push("  node [shape=circle];".to_string());
+        lines.push("  ground [shape=box, label=\"ground\"];".to_string());
+        for &coin in self.coins.iter() {
+            let corner = coin.upper_left();
+            lines.push(format!(
+                "  {} [label=\"{}\", pos=\"{},{}!\"];",
+                node_id(Node::Coin(coin)),
+                corner,
+                (corner.col as usize) * 72,
+                -((corner.row as i64) * 72),
+            ));
+        }
+        for string in self.strings.iter() {
+            lines.push(format!(
+                "  {} -- {} [tooltip=\"{}\"];",
+                node_id(string.ends.0),
+                node_id(string.ends.1),
+                string.edge,
+            ));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+}
+
+fn node_id(node: Node) -> String {
+    match node {
+        Node::Ground => "ground".to_string(),
+        Node::Coin(DotBox(corner)) => format!("coin_{}_{}", corner.row, corner.col),
+    }
+}
@@ -1,121 +1,570 @@
-use crate::game::board::{Board, BoardSize, WinnerResult};
+use std::any::Any;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::game::analysis::safe_edges;
+use crate::game::board::{Board, BoardSize, DotBox, Edge, WinnerResult, MAX_SIZE, MIN_SIZE};
+use crate::game::clock::{Clock, SystemClock};
+use crate::game::error::GameError;
+use crate::game::observer::{ConsoleObserver, GameObserver, RosterEntry};
+use crate::game::opening::random_opening;
+use crate::game::ratings::update_ratings_file;
+use crate::game::record::GameRecorder;
 use crate::players::choose::choose;
+use crate::players::player::{Player, PlayerId, SeatedPlayer, TurnContext};
 use crate::utils::{pad_end, pad_out};
 
+#[derive(Clone)]
 pub struct Opts {
-    pub board_size: BoardSize,
-    pub player_two: String,
-    pub player_one: String,
+    /* Ignored when the game starts from a position. */
+    pub rows: BoardSize,
+    pub cols: BoardSize,
+    /* Player types (or hoomin names) in seat order. */
+    pub players: Vec<String>,
+    /* The seats in the order they take turns. Empty means seat order. */
+    pub turn_order: Vec<PlayerId>,
     pub quiet: bool,
+    /* Where to save the game record, see game::record. */
+    pub record: Option<PathBuf>,
+    /*
+     * The ratings file to update once the game is over, see game::ratings.
+     * Every entry point that picks the players by type honors it, which is
+     * all of them but play_seated.
+     */
+    pub ratings: Option<PathBuf>,
+    /* How long a player gets for each move. */
+    pub move_time: Option<Duration>,
+    /* How long a player gets for all of their moves together. */
+    pub game_time: Option<Duration>,
+    /* What happens to a player who runs out of either. */
+    pub on_timeout: TimeoutPolicy,
+    /* The clock handed to players, see game::clock. */
+    pub clock: Arc<dyn Clock>,
+    /*
+     * Edges already drawn when the game starts, ex: an endgame to practise.
+     * Only the owned edges are used, the players come from the options.
+     */
+    pub position: Option<Board>,
+    /* Who moves first. None means the first seat in the turn order. */
+    pub to_move: Option<PlayerId>,
+    /*
+     * How many random safe edges to draw on top of the position before the
+     * players take over, see game::opening. They take turns drawing them.
+     */
+    pub random_opening: usize,
+    /* Picks the random opening. The same seed always gives the same game. */
+    pub seed: u64,
 }
 
-pub fn run_game(opts: &Opts) -> Result<(), String> {
-    let mut board = Board::new(opts.board_size);
-
-    print!(
-        "{}",
-        vec![
-            format!("· {} ·", pad_end("", "─", 40)),
-            format!("│ {} │", pad_out("Doots & Booxes", " ", 40)),
-            format!(
-                "│ {} │",
-                pad_out(
-                    &format!(
-                        "Playing with {} squares ({}x{} dots)",
-                        board.size(),
-                        board.dot_size(),
-                        board.dot_size()
-                    ),
-                    " ",
-                    40
-                )
-            ),
-            format!("· {} ·", pad_end("", "─", 40)),
-        ]
-        .join("\n")
-    );
+/* The CLI's defaults: two hoomins on a 10x10 board with no time limits. */
+impl Default for Opts {
+    fn default() -> Self {
+        Opts {
+            rows: 10,
+            cols: 10,
+            players: vec!["hoomin".to_string(), "hoomin".to_string()],
+            turn_order: vec![],
+            quiet: false,
+            record: None,
+            ratings: None,
+            move_time: None,
+            game_time: None,
+            on_timeout: TimeoutPolicy::Forfeit,
+            clock: Arc::new(SystemClock),
+            position: None,
+            to_move: None,
+            random_opening: 0,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeoutPolicy {
+    /* The player loses the game. */
+    Forfeit,
+    /* The engine draws a fallback edge for them and the game goes on. */
+    Fallback,
+}
+
+/* How a game ended, see play_game. */
+#[derive(Clone, Debug)]
+pub struct GameOutcome {
+    pub board: Board,
+    /*
+     * When a player forfeits, the winner is picked from everyone else by the
+     * boxes they had so far.
+     */
+    pub result: WinnerResult,
+    /* Boxes owned by each player, in turn order. */
+    pub box_counts: Vec<(PlayerId, usize)>,
+    /* How many times the turn passed to a player, streaks not included. */
+    pub turn_count: usize,
+    pub streaks: Vec<Streak>,
+    /* How long each player spent thinking, in turn order. */
+    pub time_used: Vec<(PlayerId, Duration)>,
+    pub forfeit: Option<Forfeit>,
+}
+
+impl GameOutcome {
+    pub fn boxes_for(&self, player_id: PlayerId) -> usize {
+        self.box_counts
+            .iter()
+            .find(|&&(id, _)| id == player_id)
+            .map_or(0, |&(_, count)| count)
+    }
+}
+
+/* Moves in a row where a player finished at least one box each. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Streak {
+    pub player_id: PlayerId,
+    /* Edges drawn before the streak started. */
+    pub start: usize,
+    /* How many moves finished boxes. */
+    pub length: usize,
+    pub boxes: usize,
+}
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Forfeit {
+    pub player_id: PlayerId,
+    pub reason: GameError,
+}
+
+/*
+ * Plays a game with the console output, saving a record of it afterwards if
+ * the options ask for one.
+ */
+pub fn run_game(opts: &Opts) -> Result<(), GameError> {
+    let mut console = ConsoleObserver::new(opts.quiet);
+    let mut recorder = GameRecorder::new(opts.players.clone());
+    let mut observers: Vec<&mut dyn GameObserver> = vec![&mut console];
+    if opts.record.is_some() {
+        observers.push(&mut recorder);
+    }
+
+    let played = play_game(opts, &mut observers);
+    let saved = match (&opts.record, recorder.record()) {
+        (Some(path), Some(record)) => fs::write(path, record.to_string()).map_err(|err| {
+            GameError::Record(format!(
+                "Couldn't save the game record to {}: {}",
+                path.display(),
+                err
+            ))
+        }),
+        _ => Ok(()),
+    };
+    played.and_then(finished).and(saved)
+}
+
+/*
+ * Plays a game, telling the observer about everything that happens. A player
+ * forfeiting is an error here, unlike in play_game.
+ */
+pub fn run_game_with(opts: &Opts, observer: &mut dyn GameObserver) -> Result<(), GameError> {
+    play_game(opts, observer).and_then(finished)
+}
+
+/* Turns a forfeit into the error it was caused by. */
+fn finished(outcome: GameOutcome) -> Result<(), GameError> {
+    match outcome.forfeit {
+        Some(forfeit) => Err(forfeit.reason),
+        None => Ok(()),
+    }
+}
+
+/*
+ * Plays a game without an observer, so the engine prints nothing. Players
+ * can still print on their own, like sleepy with its snoring.
+ */
+pub fn play_headless(opts: &Opts) -> Result<GameOutcome, GameError> {
+    play_game(opts, &mut ())
+}
+
+/*
+ * Plays a game to the end, or until a player forfeits, and sums it up, then
+ * updates the ratings file if the options have one. Errors only come from
+ * setting up the game or from the ratings file.
+ */
+pub fn play_game(opts: &Opts, observer: &mut dyn GameObserver) -> Result<GameOutcome, GameError> {
+    let seated = choose(&opts.players).map_err(GameError::Setup)?;
+    let outcome = play_seated(opts, seated, observer)?;
+    if let Some(path) = &opts.ratings {
+        update_ratings_file(path, &opts.players, &outcome)?;
+    }
+    Ok(outcome)
+}
+
+/* Like play_game, but with players that are already seated. */
+pub fn play_seated(
+    opts: &Opts,
+    seated: Vec<SeatedPlayer>,
+    observer: &mut dyn GameObserver,
+) -> Result<GameOutcome, GameError> {
     // I'm being pretty zealos about not using the player struct's Id in order
     // to prevent a player implementation from lying about where it actually
     // falls in the turn order.
-    let players = choose(&opts.player_one, &opts.player_two);
-    let mut player_index = 0;
+    let players: Vec<(PlayerId, Arc<dyn Player>)> = order_players(seated, &opts.turn_order)?
+        .into_iter()
+        .map(|(id, player)| (id, Arc::from(player)))
+        .collect();
+    let player_ids: Vec<PlayerId> = players.iter().map(|&(id, _)| id).collect();
+    let mut board = starting_board(opts, &player_ids)?.with_player_ids(player_ids.clone());
+    let first_index = match opts.to_move {
+        None => 0,
+        Some(to_move) => player_ids
+            .iter()
+            .position(|&id| id == to_move)
+            .ok_or_else(|| {
+                GameError::Setup(format!("Player {} to move is not in the game", to_move))
+            })?,
+    };
+    let opened = random_opening(
+        &mut board,
+        &player_ids,
+        first_index,
+        opts.random_opening,
+        opts.seed,
+    );
+    let mut player_index = (first_index + opened) % players.len();
+
+    let roster: Vec<RosterEntry> = players
+        .iter()
+        .map(|(id, player)| (*id, player.name()))
+        .collect();
+    observer.game_start(&board, &roster);
+
     let mut streak_count = 0;
+    let mut turn_count = 0;
+    let mut streaks: Vec<Streak> = vec![];
+    let mut time_used: Vec<Duration> = vec![Duration::from_secs(0); players.len()];
+    let mut forfeit = None;
 
-    for turn in 0..(board.edge_count() as usize) {
+    // Only the edges left over from the position and opening get played.
+    let drawn_count = board.iter_owned_edges().count();
+    for turn in drawn_count..(board.edge_count() as usize) {
         let (player_id, player) = &players[player_index];
+        if streak_count == 0 {
+            turn_count += 1;
+        }
+        observer.turn_start(&board, turn, *player_id, streak_count);
 
-        if !opts.quiet {
-            print!("\n\n{}\n\n", board.to_string());
+        let time_left = time_left(opts, time_used[player_index]);
+        let context = TurnContext {
+            // Note that the board clone is intentional as we don't want our
+            // players to have any way of mutating the offical board state.
+            board: board.clone(),
+            player_id: *player_id,
+            turn,
+            streak: streak_count,
+            scores: board
+                .player_ids()
+                .iter()
+                .map(|&id| (id, board.owned_boxes_count(id)))
+                .collect(),
+            players: roster.clone(),
+            time_left,
+            clock: Arc::clone(&opts.clock),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        let (answer, elapsed) = request_edge(player, context);
+        time_used[player_index] += elapsed;
 
-            if streak_count == 0 {
-                println!("Turn #{}: {}", turn + 1, player.name());
-            } else {
-                println!("Streak {}! {}", streak_count, player.name());
+        let player_edge = match (answer, opts.on_timeout) {
+            (Answer::Edge(player_edge), _) => player_edge,
+            (Answer::TimedOut, TimeoutPolicy::Fallback) => {
+                let fallback = fallback_edge(&board);
+                observer.timeout(&board, *player_id, Some(fallback));
+                fallback
             }
-        }
-
-        let owned_count_before_play = board.owned_boxes_count(*player_id);
-
-        // Note that the board clone is intentional as we don't want our
-        // players to have any way of mutating the offical board state.
-        let player_edge = player.play(board.clone());
-        match board.draw((*player_id, player_edge)) {
-            Err(_) => {
-                return Err(format!(
-                    "Player {} ({}) attempted to draw an invalid edge: {}",
-                    player_id,
-                    player.name(),
-                    player_edge,
-                ));
+            (Answer::TimedOut, TimeoutPolicy::Forfeit) => {
+                observer.timeout(&board, *player_id, None);
+                forfeit = Some(Forfeit {
+                    player_id: *player_id,
+                    reason: GameError::TimedOut {
+                        player_id: *player_id,
+                        name: player.name(),
+                        limit: time_left.unwrap_or_default(),
+                    },
+                });
+                break;
+            }
+            (Answer::Panicked(message), _) => {
+                observer.panicked(&board, *player_id, &message);
+                forfeit = Some(Forfeit {
+                    player_id: *player_id,
+                    reason: GameError::Panicked {
+                        player_id: *player_id,
+                        name: player.name(),
+                        message,
+                    },
+                });
+                break;
             }
-            _ => {}
         };
-        if !opts.quiet {
-            println!("Player {} drew: {}", player_id, player_edge);
+        if let Err(err) = board.draw((*player_id, player_edge)) {
+            observer.invalid_move(&board, (*player_id, player_edge), &err);
+            forfeit = Some(Forfeit {
+                player_id: *player_id,
+                reason: GameError::InvalidMove {
+                    player_id: *player_id,
+                    name: player.name(),
+                    edge: player_edge,
+                    error: err,
+                },
+            });
+            break;
+        }
+        observer.edge_drawn(&board, (*player_id, player_edge));
+
+        let completed: Vec<DotBox> = board
+            .associated_boxes(player_edge)
+            .into_iter()
+            .filter(|dotbox| board.box_owner(dotbox.0) == Some(*player_id))
+            .collect();
+        for &dotbox in completed.iter() {
+            observer.box_completed(&board, *player_id, dotbox);
         }
-        let owned_count_after_play = board.owned_boxes_count(*player_id);
 
-        if owned_count_before_play < owned_count_after_play {
-            if !opts.quiet {
-                println!("Player {} finished a box!", player_id);
+        if !completed.is_empty() {
+            if streak_count == 0 {
+                streaks.push(Streak {
+                    player_id: *player_id,
+                    start: turn,
+                    length: 0,
+                    boxes: 0,
+                });
             }
+            let streak = streaks.last_mut().unwrap();
+            streak.length += 1;
+            streak.boxes += completed.len();
+
             streak_count += 1;
+            observer.streak(&board, *player_id, streak_count);
         } else {
             player_index = (player_index + 1) % players.len();
             streak_count = 0;
         }
     }
 
-    print!("\n\n{}\n\n", board.to_string());
+    let box_counts: Vec<(PlayerId, usize)> = board
+        .player_ids()
+        .iter()
+        .map(|&id| (id, board.owned_boxes_count(id)))
+        .collect();
+    let result = match &forfeit {
+        None => board.winner(),
+        Some(forfeit) => {
+            let remaining: Vec<(PlayerId, usize)> = box_counts
+                .iter()
+                .filter(|&&(id, _)| id != forfeit.player_id)
+                .copied()
+                .collect();
+            WinnerResult::from_counts(&remaining)
+        }
+    };
+    if forfeit.is_none() {
+        observer.game_over(&board, &result);
+    }
 
-    let winner_message = match board.winner() {
-        WinnerResult::Winner(winner_id, winner_count) => {
-            let (_, winner) = players.iter().find(|(id, _)| *id == winner_id).unwrap();
-            format!(
-                "Player {} ({}) wins with {} boxes!",
-                winner_id,
-                winner.name(),
-                winner_count
-            )
+    Ok(GameOutcome {
+        board,
+        result,
+        box_counts,
+        turn_count,
+        streaks,
+        time_used: players.iter().map(|&(id, _)| id).zip(time_used).collect(),
+        forfeit,
+    })
+}
+
+/*
+ * The board the game starts from, which is empty unless the options have a
+ * position. Either way it has to be between MIN_SIZE and MAX_SIZE, and every
+ * edge in the position has to belong to a player in the game.
+ */
+fn starting_board(opts: &Opts, player_ids: &[PlayerId]) -> Result<Board, GameError> {
+    let (rows, cols) = match &opts.position {
+        None => (opts.rows, opts.cols),
+        Some(position) => (position.rows(), position.cols()),
+    };
+    let in_bounds = |size| (MIN_SIZE..=MAX_SIZE).contains(&size);
+    if !in_bounds(rows) || !in_bounds(cols) {
+        return Err(GameError::Setup(format!(
+            "A {}x{} board has to be between {}x{} and {}x{}",
+            rows, cols, MIN_SIZE, MIN_SIZE, MAX_SIZE, MAX_SIZE
+        )));
+    }
+
+    let position = match &opts.position {
+        None => return Ok(Board::with_dimensions(rows, cols)),
+        Some(position) => position,
+    };
+    if let Some((owner, edge)) = position
+        .iter_owned_edges()
+        .find(|(owner, _)| !player_ids.contains(owner))
+    {
+        return Err(GameError::Setup(format!(
+            "Player {} drew {} in the position but is not in the game",
+            owner, edge
+        )));
+    }
+    Ok(position.clone())
+}
+
+/* How long the player has for their next move, if there is a limit. */
+fn time_left(opts: &Opts, used: Duration) -> Option<Duration> {
+    let game_left = opts.game_time.map(|limit| limit.saturating_sub(used));
+    match (opts.move_time, game_left) {
+        (Some(move_time), Some(game_left)) => Some(move_time.min(game_left)),
+        (move_time, game_left) => move_time.or(game_left),
+    }
+}
+
+/* What came of asking a player for an edge. */
+enum Answer {
+    Edge(Edge),
+    TimedOut,
+    /* The player panicked, with the panic message. */
+    Panicked(String),
+}
+
+/*
+ * Asks the player for an edge, along with how long they took to answer. AI
+ * players think on a worker thread so the engine can walk away from them when
+ * time is up. Hoomins keep the terminal to themselves and are only checked
+ * once they answer. Either way a panicking player can't take the engine down
+ * with them.
+ */
+fn request_edge(player: &Arc<dyn Player>, context: TurnContext) -> (Answer, Duration) {
+    let started = Instant::now();
+    let answer = match context.time_left {
+        None => catch_panic(|| player.play_turn(context)),
+        Some(limit) if limit == Duration::from_secs(0) => Answer::TimedOut,
+        Some(limit) if player.is_interactive() => match catch_panic(|| player.play_turn(context)) {
+            Answer::Edge(_) if limit < started.elapsed() => Answer::TimedOut,
+            answer => answer,
+        },
+        Some(limit) => {
+            let (sender, receiver) = mpsc::channel();
+            let cancelled = Arc::clone(&context.cancelled);
+            let worker_player = Arc::clone(player);
+            let worker = thread::spawn(move || {
+                // Nobody is listening anymore if the player took too long.
+                let _ = sender.send(worker_player.play_turn(context));
+            });
+            match receiver.recv_timeout(limit) {
+                Ok(player_edge) => Answer::Edge(player_edge),
+                Err(RecvTimeoutError::Timeout) => {
+                    // There's no stopping a thread, so the worker is left
+                    // behind and only told to give up. Players that never
+                    // check the flag keep a core busy until they answer.
+                    cancelled.store(true, Ordering::Relaxed);
+                    Answer::TimedOut
+                }
+                Err(RecvTimeoutError::Disconnected) => match worker.join() {
+                    Err(payload) => Answer::Panicked(panic_message(payload.as_ref())),
+                    Ok(_) => Answer::Panicked("The player went away".to_string()),
+                },
+            }
         }
+    };
+    (answer, started.elapsed())
+}
+
+fn catch_panic<F: FnOnce() -> Edge>(play: F) -> Answer {
+    // Players only get a clone of the board, so there's nothing of ours they
+    // could leave broken halfway through a panic.
+    match panic::catch_unwind(AssertUnwindSafe(play)) {
+        Ok(player_edge) => Answer::Edge(player_edge),
+        Err(payload) => Answer::Panicked(panic_message(payload.as_ref())),
+    }
+}
+
+/* Panics usually carry a &str or a String, anything else is a mystery. */
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.to_string()
+    } else {
+        "Unknown panic".to_string()
+    }
+}
+
+/*
+ * The edge drawn for a player who ran out of time: the first one that doesn't
+ * hand a box over, or the first free one if they all do.
+ */
+fn fallback_edge(board: &Board) -> Edge {
+    safe_edges(board)
+        .first()
+        .copied()
+        .or_else(|| board.iter_edges().find(|&edge| board.is_free(edge)))
+        .unwrap()
+}
+
+/* Announces the result, looking up the winner's name with `name_of`. */
+pub fn winner_message<F: Fn(PlayerId) -> String>(result: &WinnerResult, name_of: F) -> String {
+    match result {
+        WinnerResult::Winner(winner_id, winner_count) => format!(
+            "Player {} ({}) wins with {} boxes!",
+            winner_id,
+            name_of(*winner_id),
+            winner_count
+        ),
         WinnerResult::Tie(tied_ids, tied_count) => format!(
             "A tie between {:?} with {} boxes each.",
             tied_ids, tied_count
         ),
         WinnerResult::None => "I think something went wrong...".to_string(),
-    };
+    }
+}
 
-    print!(
-        "{}",
-        vec![
-            format!("· {} ·", pad_end("", "─", winner_message.len())),
-            format!("│ {} │", pad_out("GAME OVER", " ", winner_message.len())),
-            format!("│ {} │", winner_message),
-            format!("· {} ·", pad_end("", "─", winner_message.len())),
-        ]
-        .join("\n")
-    );
+pub fn game_over_banner(winner_message: &str) -> String {
+    let width = winner_message.chars().count();
+    [
+        format!("· {} ·", pad_end("", "─", width)),
+        format!("│ {} │", pad_out("GAME OVER", " ", width)),
+        format!("│ {} │", winner_message),
+        format!("· {} ·", pad_end("", "─", width)),
+    ]
+    .join("\n")
+}
 
-    Ok(())
+/*
+ * Rearranges the seated players into the turn order. Every seat has to show up
+ * exactly once.
+ */
+fn order_players(
+    mut seated: Vec<SeatedPlayer>,
+    turn_order: &[PlayerId],
+) -> Result<Vec<SeatedPlayer>, GameError> {
+    if turn_order.is_empty() {
+        return Ok(seated);
+    }
+
+    let mut sorted_order = turn_order.to_vec();
+    sorted_order.sort();
+    let seats: Vec<PlayerId> = seated.iter().map(|&(id, _)| id).collect();
+    if sorted_order != seats {
+        return Err(GameError::Setup(format!(
+            "Turn order {:?} does not match the seated players {:?}",
+            turn_order, seats
+        )));
+    }
+
+    let mut ordered = vec![];
+    for &id in turn_order {
+        let index = seated.iter().position(|&(seat, _)| seat == id).unwrap();
+        ordered.push(seated.remove(index));
+    }
+    Ok(ordered)
 }
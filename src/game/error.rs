@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::game::board::{BoardSize, Edge};
+use crate::players::player::PlayerId;
+
+/* Why an edge couldn't be drawn on a board. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DrawError {
+    /* The dots aren't next to each other. */
+    InvalidEdge(Edge),
+    DoesNotFit {
+        edge: Edge,
+        rows: BoardSize,
+        cols: BoardSize,
+    },
+    AlreadyDrawn(Edge),
+}
+
+impl fmt::Display for DrawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrawError::InvalidEdge(edge) => write!(f, "Cannot draw invalid edge: {}", edge),
+            DrawError::DoesNotFit { edge, rows, cols } => write!(
+                f,
+                "Edge {:?} does not fit in board of size {}x{}",
+                edge, rows, cols
+            ),
+            DrawError::AlreadyDrawn(edge) => write!(f, "Cannot redraw edge: {}", edge),
+        }
+    }
+}
+
+impl Error for DrawError {}
+
+/* Lets DrawErrors bubble up through the parsers, which report Strings. */
+impl From<DrawError> for String {
+    fn from(error: DrawError) -> String {
+        error.to_string()
+    }
+}
+
+/* Why a game couldn't be played to the end. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameError {
+    /* The options don't make a game, like a bad player count or turn order. */
+    Setup(String),
+    /* A player tried to draw an edge the board wouldn't take. */
+    InvalidMove {
+        player_id: PlayerId,
+        name: String,
+        edge: Edge,
+        error: DrawError,
+    },
+    /* A player used up the time they had for a move or for the game. */
+    TimedOut {
+        player_id: PlayerId,
+        name: String,
+        limit: Duration,
+    },
+    /* A player panicked instead of picking an edge. */
+    Panicked {
+        player_id: PlayerId,
+        name: String,
+        message: String,
+    },
+    /* A game record couldn't be read, written or understood. */
+    Record(String),
+    /* The ratings file couldn't be read, written or understood. */
+    Ratings(String),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::Setup(message) => write!(f, "{}", message),
+            GameError::InvalidMove {
+                player_id,
+                name,
+                edge,
+                ..
+            } => write!(
+                f,
+                "Player {} ({}) attempted to draw an invalid edge: {}",
+                player_id, name, edge
+            ),
+            GameError::TimedOut {
+                player_id,
+                name,
+                limit,
+            } => write!(
+                f,
+                "Player {} ({}) ran out of time with {:?} to play",
+                player_id, name, limit
+            ),
+            GameError::Panicked {
+                player_id,
+                name,
+                message,
+            } => write!(f, "Player {} ({}) panicked: {}", player_id, name, message),
+            GameError::Record(message) => write!(f, "{}", message),
+            GameError::Ratings(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for GameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GameError::InvalidMove { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
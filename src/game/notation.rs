@@ -0,0 +1,177 @@
+/*
+ * Compact text notation for positions, meant for fixture files and bug
+ * reports. A board is written as its dimensions, its players, and then every
+ * owned edge in the order it was drawn:
+ *
+ *   2x3 p1,2 1:0,0h 2:0,0v 1:1,2v
+ *
+ * Each edge is named by its upper or left dot followed by the direction it
+ * goes from there: `h` for right and `v` for down. Any whitespace separates
+ * the parts, and lines starting with `#` are ignored.
+ */
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game::board::{dot, Board, BoardSize, Edge, OwnedEdge, MAX_SIZE, MIN_SIZE};
+use crate::players::player::PlayerId;
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{} p{}",
+            self.rows(),
+            self.cols(),
+            format_seats(self.player_ids())
+        )?;
+        for owned_edge in self.iter_owned_edges() {
+            write!(f, " {}", format_owned_edge(owned_edge))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Board {
+    type Err = String;
+
+    fn from_str(notation: &str) -> Result<Board, String> {
+        let mut parts = notation
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(|line| line.split_whitespace());
+
+        let dimensions = parts.next().ok_or("Board notation is empty")?;
+        let (rows, cols) = parse_dimensions(dimensions)?;
+        let mut board = Board::with_dimensions(rows, cols);
+
+        let mut moves: Vec<&str> = parts.collect();
+        if let Some(players) = moves.first().and_then(|part| part.strip_prefix('p')) {
+            board = board.with_player_ids(parse_seats(players)?);
+            moves.remove(0);
+        }
+
+        for part in moves {
+            let owned_edge = parse_owned_edge_on(part, rows, cols)?;
+            if !board.player_ids().contains(&owned_edge.0) {
+                return Err(format!("Player in {} is not in the game", part));
+            }
+            board.draw(owned_edge)?;
+        }
+        Ok(board)
+    }
+}
+
+/* Formats an edge as its upper or left dot and direction, ex: 3,4h */
+pub fn format_edge(Edge(d1, d2): Edge) -> String {
+    let start = d1.min(d2);
+    let end = d1.max(d2);
+    let direction = if start.row == end.row { 'h' } else { 'v' };
+    format!("{},{}{}", start.row, start.col, direction)
+}
+
+pub fn parse_edge(notation: &str) -> Result<Edge, String> {
+    let (row, col, horizontal) = parse_edge_parts(notation)?;
+    let end = if horizontal {
+        col.checked_add(1).map(|end_col| dot(row, end_col))
+    } else {
+        row.checked_add(1).map(|end_row| dot(end_row, col))
+    };
+    end.map(|end| Edge(dot(row, col), end))
+        .ok_or(format!("Edge {} doesn't make sense", notation))
+}
+
+/* Like parse_edge, but the edge also has to fit on a board of the given size. */
+pub fn parse_edge_on(notation: &str, rows: BoardSize, cols: BoardSize) -> Result<Edge, String> {
+    let (row, col, horizontal) = parse_edge_parts(notation)?;
+    let fits = if horizontal {
+        row <= rows && col < cols
+    } else {
+        row < rows && col <= cols
+    };
+    if !fits {
+        return Err(format!(
+            "Edge {} does not fit in board of size {}x{}",
+            notation, rows, cols
+        ));
+    }
+    parse_edge(notation)
+}
+
+/* The upper or left dot's row and column, and whether the edge is horizontal. */
+fn parse_edge_parts(notation: &str) -> Result<(BoardSize, BoardSize, bool), String> {
+    let invalid = || format!("Edge {} doesn't make sense", notation);
+    let direction = notation.chars().last().ok_or_else(invalid)?;
+    let coords = &notation[..notation.len() - direction.len_utf8()];
+    let (row_str, col_str) = coords.split_once(',').ok_or_else(invalid)?;
+    let row = row_str.parse::<BoardSize>().map_err(|_| invalid())?;
+    let col = col_str.parse::<BoardSize>().map_err(|_| invalid())?;
+    match direction {
+        'h' => Ok((row, col, true)),
+        'v' => Ok((row, col, false)),
+        _ => Err(invalid()),
+    }
+}
+
+/* Formats an owned edge as the owner's seat and the edge, ex: 2:3,4h */
+pub fn format_owned_edge((owner, edge): OwnedEdge) -> String {
+    format!("{}:{}", owner.seat(), format_edge(edge))
+}
+
+pub fn parse_owned_edge(notation: &str) -> Result<OwnedEdge, String> {
+    let (seat_str, edge_str) = notation
+        .split_once(':')
+        .ok_or(format!("Move {} is missing the player", notation))?;
+    Ok((parse_seat(seat_str)?, parse_edge(edge_str)?))
+}
+
+/* Like parse_owned_edge, but the edge also has to fit on the board. */
+pub fn parse_owned_edge_on(
+    notation: &str,
+    rows: BoardSize,
+    cols: BoardSize,
+) -> Result<OwnedEdge, String> {
+    let (seat_str, edge_str) = notation
+        .split_once(':')
+        .ok_or(format!("Move {} is missing the player", notation))?;
+    Ok((parse_seat(seat_str)?, parse_edge_on(edge_str, rows, cols)?))
+}
+
+/*
+ * Parses board dimensions as rows by columns, ex: 3x5. Both have to be
+ * between MIN_SIZE and MAX_SIZE.
+ */
+pub fn parse_dimensions(notation: &str) -> Result<(BoardSize, BoardSize), String> {
+    let invalid = || format!("Board size {} doesn't make sense", notation);
+    let (rows_str, cols_str) = notation.split_once('x').ok_or_else(invalid)?;
+    let rows = rows_str.parse::<BoardSize>().map_err(|_| invalid())?;
+    let cols = cols_str.parse::<BoardSize>().map_err(|_| invalid())?;
+    let in_bounds = |size| (MIN_SIZE..=MAX_SIZE).contains(&size);
+    if !in_bounds(rows) || !in_bounds(cols) {
+        return Err(format!(
+            "Board size {} has to be between {}x{} and {}x{}",
+            notation, MIN_SIZE, MIN_SIZE, MAX_SIZE, MAX_SIZE
+        ));
+    }
+    Ok((rows, cols))
+}
+
+/* Formats seats separated by commas, ex: 3,1,2 */
+pub fn format_seats(ids: &[PlayerId]) -> String {
+    let seats: Vec<String> = ids.iter().map(|id| id.seat().to_string()).collect();
+    seats.join(",")
+}
+
+pub fn parse_seats(notation: &str) -> Result<Vec<PlayerId>, String> {
+    notation
+        .split(',')
+        .map(|seat| parse_seat(seat.trim()))
+        .collect()
+}
+
+pub fn parse_seat(notation: &str) -> Result<PlayerId, String> {
+    notation
+        .parse::<usize>()
+        .ok()
+        .and_then(PlayerId::from_seat)
+        .ok_or(format!("Player {} doesn't make sense", notation))
+}
@@ -0,0 +1,204 @@
+use crate::game::board::{Board, DotBox, Edge, OwnedEdge, WinnerResult};
+use crate::game::engine::{game_over_banner, winner_message};
+use crate::game::error::DrawError;
+use crate::players::player::PlayerId;
+use crate::utils::{pad_end, pad_out};
+
+/* A player's seat and name, as handed to observers. */
+pub type RosterEntry = (PlayerId, String);
+
+/*
+ * Hooks into the engine's turn loop. Every callback gets the official board as
+ * it stands at that point in the game, and does nothing unless overridden.
+ */
+pub trait GameObserver {
+    /* The players are in turn order. */
+    fn game_start(&mut self, _board: &Board, _players: &[RosterEntry]) {}
+
+    /*
+     * Before a player is asked for an edge. The turn counts every edge drawn
+     * so far, and the streak how many moves the player has made since their
+     * turn started, each of which finished at least one box.
+     */
+    fn turn_start(&mut self, _board: &Board, _turn: usize, _player_id: PlayerId, _streak: usize) {}
+
+    fn edge_drawn(&mut self, _board: &Board, _owned_edge: OwnedEdge) {}
+
+    /* Called for every box the edge finished, so twice for a double box. */
+    fn box_completed(&mut self, _board: &Board, _player_id: PlayerId, _dotbox: DotBox) {}
+
+    /* The player finished at least one box and gets to go again. */
+    fn streak(&mut self, _board: &Board, _player_id: PlayerId, _streak: usize) {}
+
+    /*
+     * The player ran out of time. The engine either drew the fallback edge
+     * for them, or ended the game if there isn't one.
+     */
+    fn timeout(&mut self, _board: &Board, _player_id: PlayerId, _fallback: Option<Edge>) {}
+
+    /* The player panicked while picking an edge, which ends the game. */
+    fn panicked(&mut self, _board: &Board, _player_id: PlayerId, _message: &str) {}
+
+    /* The player tried an edge that can't be drawn, which ends the game. */
+    fn invalid_move(&mut self, _board: &Board, _owned_edge: OwnedEdge, _error: &DrawError) {}
+
+    fn game_over(&mut self, _board: &Board, _result: &WinnerResult) {}
+}
+
+/* Ignores everything, for games nobody is watching. */
+impl GameObserver for () {}
+
+/* Lets the engine notify any number of observers as if they were one. */
+impl GameObserver for Vec<&mut dyn GameObserver> {
+    fn game_start(&mut self, board: &Board, players: &[RosterEntry]) {
+        for observer in self.iter_mut() {
+            observer.game_start(board, players);
+        }
+    }
+
+    fn turn_start(&mut self, board: &Board, turn: usize, player_id: PlayerId, streak: usize) {
+        for observer in self.iter_mut() {
+            observer.turn_start(board, turn, player_id, streak);
+        }
+    }
+
+    fn edge_drawn(&mut self, board: &Board, owned_edge: OwnedEdge) {
+        for observer in self.iter_mut() {
+            observer.edge_drawn(board, owned_edge);
+        }
+    }
+
+    fn box_completed(&mut self, board: &Board, player_id: PlayerId, dotbox: DotBox) {
+        for observer in self.iter_mut() {
+            observer.box_completed(board, player_id, dotbox);
+        }
+    }
+
+    fn streak(&mut self, board: &Board, player_id: PlayerId, streak: usize) {
+        for observer in self.iter_mut() {
+            observer.streak(board, player_id, streak);
+        }
+    }
+
+    fn timeout(&mut self, board: &Board, player_id: PlayerId, fallback: Option<Edge>) {
+        for observer in self.iter_mut() {
+            observer.timeout(board, player_id, fallback);
+        }
+    }
+
+    fn panicked(&mut self, board: &Board, player_id: PlayerId, message: &str) {
+        for observer in self.iter_mut() {
+            observer.panicked(board, player_id, message);
+        }
+    }
+
+    fn invalid_move(&mut self, board: &Board, owned_edge: OwnedEdge, error: &DrawError) {
+        for observer in self.iter_mut() {
+            observer.invalid_move(board, owned_edge, error);
+        }
+    }
+
+    fn game_over(&mut self, board: &Board, result: &WinnerResult) {
+        for observer in self.iter_mut() {
+            observer.game_over(board, result);
+        }
+    }
+}
+
+/*
+ * Prints the game to stdout the way doots always has. In quiet mode only the
+ * title and the final board are shown.
+ */
+pub struct ConsoleObserver {
+    quiet: bool,
+    players: Vec<RosterEntry>,
+}
+
+impl ConsoleObserver {
+    pub fn new(quiet: bool) -> ConsoleObserver {
+        ConsoleObserver {
+            quiet,
+            players: vec![],
+        }
+    }
+
+    fn name_of(&self, player_id: PlayerId) -> String {
+        self.players
+            .iter()
+            .find(|(id, _)| *id == player_id)
+            .map_or(player_id.to_string(), |(_, name)| name.to_string())
+    }
+}
+
+impl GameObserver for ConsoleObserver {
+    fn game_start(&mut self, board: &Board, players: &[RosterEntry]) {
+        self.players = players.to_vec();
+        print!(
+            "{}",
+            [
+                format!("· {} ·", pad_end("", "─", 40)),
+                format!("│ {} │", pad_out("Doots & Booxes", " ", 40)),
+                format!(
+                    "│ {} │",
+                    pad_out(
+                        &format!(
+                            "Playing with {}x{} squares ({}x{} dots)",
+                            board.rows(),
+                            board.cols(),
+                            board.dot_rows(),
+                            board.dot_cols()
+                        ),
+                        " ",
+                        40
+                    )
+                ),
+                format!("· {} ·", pad_end("", "─", 40)),
+            ]
+            .join("\n")
+        );
+    }
+
+    fn turn_start(&mut self, board: &Board, turn: usize, player_id: PlayerId, streak: usize) {
+        if self.quiet {
+            return;
+        }
+        print!("\n\n{}\n\n", board.drawing());
+
+        if streak == 0 {
+            println!("Turn #{}: {}", turn + 1, self.name_of(player_id));
+        } else {
+            println!("Streak {}! {}", streak, self.name_of(player_id));
+        }
+    }
+
+    fn edge_drawn(&mut self, _board: &Board, (player_id, edge): OwnedEdge) {
+        if !self.quiet {
+            println!("Player {} drew: {}", player_id, edge);
+        }
+    }
+
+    fn timeout(&mut self, _board: &Board, player_id: PlayerId, fallback: Option<Edge>) {
+        if self.quiet {
+            return;
+        }
+        match fallback {
+            Some(_) => println!(
+                "Player {} ran out of time, so the engine moves for them.",
+                player_id
+            ),
+            None => println!("Player {} ran out of time!", player_id),
+        }
+    }
+
+    fn streak(&mut self, _board: &Board, player_id: PlayerId, _streak: usize) {
+        if !self.quiet {
+            println!("Player {} finished a box!", player_id);
+        }
+    }
+
+    fn game_over(&mut self, board: &Board, result: &WinnerResult) {
+        print!("\n\n{}\n\n", board.drawing());
+        let message = winner_message(result, |id| self.name_of(id));
+        print!("{}", game_over_banner(&message));
+    }
+}
@@ -0,0 +1,35 @@
+/*
+ * Random openings, so games between deterministic players don't all play out
+ * the same way. Only safe edges are drawn, so nobody is handed a box before
+ * the players take over.
+ */
+use crate::game::analysis::safe_edges;
+use crate::game::board::Board;
+use crate::players::player::PlayerId;
+use crate::utils::SeededRng;
+
+/*
+ * Draws up to `moves` random safe edges, taking turns in `turn_order` starting
+ * at `first`. Stops early when there are no safe edges left, and returns how
+ * many were drawn. The same seed always draws the same edges.
+ */
+pub fn random_opening(
+    board: &mut Board,
+    turn_order: &[PlayerId],
+    first: usize,
+    moves: usize,
+    seed: u64,
+) -> usize {
+    let mut rng = SeededRng::new(seed);
+    for drawn in 0..moves {
+        let edges = safe_edges(board);
+        if edges.is_empty() {
+            return drawn;
+        }
+        let player_id = turn_order[(first + drawn) % turn_order.len()];
+        let edge = edges[rng.below(edges.len())];
+        // Safe edges are free and never finish a box, so the turn always passes.
+        board.draw((player_id, edge)).unwrap();
+    }
+    moves
+}
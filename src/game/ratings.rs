@@ -0,0 +1,297 @@
+/*
+ * Glicko ratings for the AI players, so changes to an AI can be measured by
+ * whether it climbs. Every rating comes with a deviation saying how sure we
+ * are of it, which shrinks as the player plays more games.
+ *
+ * Ratings are kept in a plain text file, one player per line:
+ *
+ *   # player rating deviation games
+ *   doot 1712.4 48.1 120
+ *   boox 1287.6 48.1 120
+ *
+ * Every game is its own rating period, with each player rated against all of
+ * their opponents at once.
+ */
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::game::board::WinnerResult;
+use crate::game::engine::GameOutcome;
+use crate::game::error::GameError;
+use crate::players::choose::AI_KEYS;
+use crate::utils::pad_end;
+
+pub const INITIAL_RATING: f64 = 1500.0;
+pub const INITIAL_DEVIATION: f64 = 350.0;
+/*
+ * Deviations never shrink below this, so a player whose code changed can
+ * still move after thousands of games.
+ */
+pub const MIN_DEVIATION: f64 = 30.0;
+
+/* Glicko's q, which turns rating points into natural log odds. */
+const Q: f64 = std::f64::consts::LN_10 / 400.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub games: usize,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Rating {
+            rating: INITIAL_RATING,
+            deviation: INITIAL_DEVIATION,
+            games: 0,
+        }
+    }
+}
+
+impl Rating {
+    /* Where the player's true rating is, 95% of the time. */
+    pub fn interval(&self) -> (f64, f64) {
+        (
+            self.rating - 1.96 * self.deviation,
+            self.rating + 1.96 * self.deviation,
+        )
+    }
+
+    /* The chance of beating the opponent, counting a tie as half. */
+    pub fn expected_score(&self, opponent: &Rating) -> f64 {
+        1.0 / (1.0 + 10f64.powf(-g(opponent.deviation) * (self.rating - opponent.rating) / 400.0))
+    }
+}
+
+/* Glicko's g, which discounts results against players we aren't sure of. */
+fn g(deviation: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * Q * Q * deviation * deviation / (PI * PI)).sqrt()
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ratings {
+    players: BTreeMap<String, Rating>,
+}
+
+impl Ratings {
+    pub fn new() -> Ratings {
+        Ratings::default()
+    }
+
+    /* Reads the ratings file, which doesn't have to exist yet. */
+    pub fn load(path: &Path) -> Result<Ratings, GameError> {
+        match fs::read_to_string(path) {
+            Ok(text) => text
+                .parse::<Ratings>()
+                .map_err(|err| GameError::Ratings(format!("{} in {}", err, path.display()))),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Ratings::new()),
+            Err(err) => Err(GameError::Ratings(format!(
+                "Couldn't read the ratings from {}: {}",
+                path.display(),
+                err
+            ))),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), GameError> {
+        fs::write(path, self.to_string()).map_err(|err| {
+            GameError::Ratings(format!(
+                "Couldn't save the ratings to {}: {}",
+                path.display(),
+                err
+            ))
+        })
+    }
+
+    /* A player's rating, or the starting one if they haven't played. */
+    pub fn get(&self, player: &str) -> Rating {
+        self.players.get(player).copied().unwrap_or_default()
+    }
+
+    /* Highest rated first. */
+    pub fn ranked(&self) -> Vec<(&String, &Rating)> {
+        let mut ranked: Vec<(&String, &Rating)> = self.players.iter().collect();
+        ranked.sort_by(|a, b| b.1.rating.partial_cmp(&a.1.rating).unwrap());
+        ranked
+    }
+
+    /*
+     * Rates a game from each player's score. A higher score beats a lower one
+     * and equal scores tie, so every pair of players counts as a result.
+     * Pairs of the same player, like boox against boox, say nothing about how
+     * strong it is and are skipped. A player in more than one seat gets one
+     * update with the results from all of them.
+     */
+    pub fn record_game(&mut self, scores: &[(String, i64)]) {
+        let mut players: Vec<&String> = scores.iter().map(|(player, _)| player).collect();
+        players.sort();
+        players.dedup();
+        let before: Vec<Rating> = scores.iter().map(|(player, _)| self.get(player)).collect();
+
+        for player in players {
+            let rating = self.get(player);
+            let mut variance_sum = 0.0;
+            let mut improvement = 0.0;
+            for (seat_player, score) in scores.iter().filter(|(p, _)| p == player) {
+                for (opponent_index, (opponent_player, opponent_score)) in scores.iter().enumerate()
+                {
+                    if opponent_player == seat_player {
+                        continue;
+                    }
+                    let opponent = before[opponent_index];
+                    let expected = rating.expected_score(&opponent);
+                    let actual = match score.cmp(opponent_score) {
+                        std::cmp::Ordering::Greater => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Less => 0.0,
+                    };
+                    let weight = g(opponent.deviation);
+                    variance_sum += weight * weight * expected * (1.0 - expected);
+                    improvement += weight * (actual - expected);
+                }
+            }
+            if variance_sum == 0.0 {
+                continue;
+            }
+
+            let precision = 1.0 / (rating.deviation * rating.deviation) + Q * Q * variance_sum;
+            self.players.insert(
+                player.to_string(),
+                Rating {
+                    rating: rating.rating + Q / precision * improvement,
+                    deviation: (1.0 / precision).sqrt().max(MIN_DEVIATION),
+                    games: rating.games + 1,
+                },
+            );
+        }
+    }
+
+    /* The ratings as a table, with the 95% interval for each player. */
+    pub fn table(&self) -> String {
+        let mut lines = vec![format!(
+            "{}{}{}Games",
+            pad_end("Player", " ", 12),
+            pad_end("Rating", " ", 10),
+            pad_end("95% interval", " ", 18)
+        )];
+        for (player, rating) in self.ranked() {
+            let (low, high) = rating.interval();
+            lines.push(format!(
+                "{}{}{}{}",
+                pad_end(player, " ", 12),
+                pad_end(&format!("{:.0}", rating.rating), " ", 10),
+                pad_end(&format!("{:.0} to {:.0}", low, high), " ", 18),
+                rating.games
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/*
+ * The scores to rate a game by: the boxes each player got, except that a
+ * player who forfeited loses to everyone. None unless every player is an AI,
+ * as hoomins aren't rated.
+ */
+pub fn outcome_scores(kinds: &[String], outcome: &GameOutcome) -> Option<Vec<(String, i64)>> {
+    if !kinds.iter().all(|kind| AI_KEYS.contains(&kind.as_str())) {
+        return None;
+    }
+    let forfeited = outcome.forfeit.as_ref().map(|forfeit| forfeit.player_id);
+    let winners = match &outcome.result {
+        WinnerResult::Winner(id, _) => vec![*id],
+        WinnerResult::Tie(ids, _) => ids.clone(),
+        WinnerResult::None => vec![],
+    };
+    Some(
+        outcome
+            .box_counts
+            .iter()
+            .map(|&(id, boxes)| {
+                let score = if Some(id) == forfeited {
+                    -1
+                } else if winners.contains(&id) {
+                    // A player can win a forfeited game with fewer boxes.
+                    i64::MAX
+                } else {
+                    boxes as i64
+                };
+                (kinds[id.seat() - 1].to_string(), score)
+            })
+            .collect(),
+    )
+}
+
+/* Rates the game in the ratings file, if every player in it is an AI. */
+pub fn update_ratings_file(
+    path: &Path,
+    kinds: &[String],
+    outcome: &GameOutcome,
+) -> Result<(), GameError> {
+    let scores = match outcome_scores(kinds, outcome) {
+        Some(scores) => scores,
+        None => return Ok(()),
+    };
+    let mut ratings = Ratings::load(path)?;
+    ratings.record_game(&scores);
+    ratings.save(path)
+}
+
+impl fmt::Display for Ratings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# player rating deviation games")?;
+        for (player, rating) in self.players.iter() {
+            writeln!(
+                f,
+                "{} {:.1} {:.1} {}",
+                player, rating.rating, rating.deviation, rating.games
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Ratings {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Ratings, String> {
+        let mut ratings = Ratings::new();
+        for (line_index, raw_line) in text.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || {
+                format!(
+                    "Line {}: Rating {} doesn't make sense",
+                    line_index + 1,
+                    line
+                )
+            };
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let (player, rating_str, deviation_str, games_str) = match parts.as_slice() {
+                [player, rating, deviation, games] => (player, rating, deviation, games),
+                _ => return Err(invalid()),
+            };
+            let rating = Rating {
+                rating: rating_str.parse::<f64>().map_err(|_| invalid())?,
+                deviation: deviation_str.parse::<f64>().map_err(|_| invalid())?,
+                games: games_str.parse::<usize>().map_err(|_| invalid())?,
+            };
+            if !rating.rating.is_finite()
+                || !rating.deviation.is_finite()
+                || rating.deviation <= 0.0
+            {
+                return Err(invalid());
+            }
+            ratings.players.insert(player.to_string(), rating);
+        }
+        Ok(ratings)
+    }
+}
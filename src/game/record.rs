@@ -0,0 +1,491 @@
+/*
+ * Game records, the doots take on PGN. A record starts with tag pairs holding
+ * everything about the game besides the moves, followed by a blank line and
+ * one move per line:
+ *
+ *   [Game "Doots & Booxes"]
+ *   [Date "2026.10.18"]
+ *   [Size "2x3"]
+ *   [Player1 "doot"]
+ *   [Name1 "Doot One"]
+ *   [Player2 "boox"]
+ *   [Name2 "Boox Two"]
+ *   [Order "1,2"]
+ *   [Result "2"]
+ *
+ *   1. 1:0,0h
+ *   2. 2:0,0v
+ *   3. 1:1,0h
+ *   4. 2:0,1v
+ *   5. 2:0,1h +1
+ *
+ * Moves are numbered from 1 like the engine's "Turn #n" and use the owned
+ * edge notation from game::notation. A `+n` marker means the move continued a
+ * streak, after n moves earlier in the same turn that each finished at least
+ * one box. A move that finishes two boxes at once still only adds 1. The
+ * result is the winning seat, every tied seat separated by commas, or `*`
+ * when the game didn't finish.
+ *
+ * Games started from a position have a Position tag holding the owned edges
+ * that were already drawn, ex: [Position "1:0,0h 2:1,1v"]
+ */
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Local, NaiveDate};
+
+use crate::game::board::{Board, BoardSize, OwnedEdge, WinnerResult};
+use crate::game::notation::{
+    format_owned_edge, format_seats, parse_dimensions, parse_owned_edge, parse_seat, parse_seats,
+};
+use crate::game::observer::{GameObserver, RosterEntry};
+use crate::players::player::PlayerId;
+
+const GAME_TAG: &str = "Doots & Booxes";
+const DATE_FORMAT: &str = "%Y.%m.%d";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedPlayer {
+    pub id: PlayerId,
+    /* The type the player was chosen by, ex: doot */
+    pub kind: String,
+    pub name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecordedMove {
+    pub owned_edge: OwnedEdge,
+    /*
+     * How many moves earlier in the same turn finished at least one box. This
+     * counts moves rather than boxes, so a double box only adds 1.
+     */
+    pub streak: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    pub rows: BoardSize,
+    pub cols: BoardSize,
+    pub date: Option<NaiveDate>,
+    /* In seat order. */
+    pub players: Vec<RecordedPlayer>,
+    /* The seats in the order they took turns. */
+    pub turn_order: Vec<PlayerId>,
+    /* Edges already drawn when the game started, see engine::Opts. */
+    pub setup: Vec<OwnedEdge>,
+    pub moves: Vec<RecordedMove>,
+    /* None until the game is over. */
+    pub result: Option<WinnerResult>,
+}
+
+impl GameRecord {
+    pub fn new(rows: BoardSize, cols: BoardSize, players: Vec<RecordedPlayer>) -> GameRecord {
+        let turn_order = players.iter().map(|player| player.id).collect();
+        GameRecord {
+            rows,
+            cols,
+            date: None,
+            players,
+            turn_order,
+            setup: vec![],
+            moves: vec![],
+            result: None,
+        }
+    }
+
+    pub fn ply_count(&self) -> usize {
+        self.moves.len()
+    }
+
+    /* The board the way the recorded game started, before the first move. */
+    pub fn initial_board(&self) -> Result<Board, String> {
+        let mut board =
+            Board::with_dimensions(self.rows, self.cols).with_player_ids(self.turn_order.clone());
+        board
+            .draw_many(self.setup.clone())
+            .map_err(|err| format!("Position: {}", err))?;
+        Ok(board)
+    }
+
+    /* The board after the first `ply` moves of the game. */
+    pub fn board_at(&self, ply: usize) -> Result<Board, String> {
+        if self.moves.len() < ply {
+            return Err(format!(
+                "The game only has {} moves, not {}",
+                self.moves.len(),
+                ply
+            ));
+        }
+        let mut board = self.initial_board()?;
+        board.draw_many(self.moves[..ply].iter().map(|m| m.owned_edge).collect())?;
+        Ok(board)
+    }
+
+    pub fn final_board(&self) -> Result<Board, String> {
+        self.board_at(self.moves.len())
+    }
+
+    /*
+     * Replays the moves to make sure they are legal, that they go around the
+     * turn order, that the streak markers are right and that the result
+     * agrees with the final board. Whoever makes the first move starts the
+     * turn order, as a game can be started with any player to move.
+     */
+    pub fn validate(&self) -> Result<(), String> {
+        for &(player_id, _) in self.setup.iter() {
+            if !self.turn_order.contains(&player_id) {
+                return Err(format!("Position: Player {} is not in the game", player_id));
+            }
+        }
+        let mut board = self.initial_board()?;
+        let mut streak = 0;
+        let mut to_move = None;
+        for (index, recorded) in self.moves.iter().enumerate() {
+            let (player_id, edge) = recorded.owned_edge;
+            let seat = match self.turn_order.iter().position(|&id| id == player_id) {
+                Some(seat) => seat,
+                None => {
+                    return Err(format!(
+                        "Move {}: Player {} is not in the game",
+                        index + 1,
+                        player_id
+                    ))
+                }
+            };
+            if to_move.unwrap_or(seat) != seat {
+                return Err(format!(
+                    "Move {}: Player {} moved out of turn",
+                    index + 1,
+                    player_id
+                ));
+            }
+            if recorded.streak != streak {
+                return Err(format!(
+                    "Move {}: Expected a streak of {} but the record says {}",
+                    index + 1,
+                    streak,
+                    recorded.streak
+                ));
+            }
+            if board.would_claim_box(edge) {
+                streak += 1;
+                to_move = Some(seat);
+            } else {
+                streak = 0;
+                to_move = Some((seat + 1) % self.turn_order.len());
+            }
+            board
+                .draw(recorded.owned_edge)
+                .map_err(|err| format!("Move {}: {}", index + 1, err))?;
+        }
+
+        if let Some(result) = &self.result {
+            if !board.is_full() {
+                return Err("The record has a result but the board isn't full".to_string());
+            }
+            if *result != board.winner() {
+                return Err(format!(
+                    "The record says {} but the board says {}",
+                    format_result(&self.result),
+                    format_result(&Some(board.winner()))
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/*
+ * Builds a GameRecord while a game is played. Records of games cut short by an
+ * invalid move have no result.
+ */
+pub struct GameRecorder {
+    /* Player types in seat order, as given to players::choose. */
+    kinds: Vec<String>,
+    streak: usize,
+    record: Option<GameRecord>,
+}
+
+impl GameRecorder {
+    pub fn new(kinds: Vec<String>) -> GameRecorder {
+        GameRecorder {
+            kinds,
+            streak: 0,
+            record: None,
+        }
+    }
+
+    /* The game so far, if one has started. */
+    pub fn record(&self) -> Option<&GameRecord> {
+        self.record.as_ref()
+    }
+}
+
+impl GameObserver for GameRecorder {
+    fn game_start(&mut self, board: &Board, players: &[RosterEntry]) {
+        let mut recorded_players: Vec<RecordedPlayer> = players
+            .iter()
+            .map(|(id, name)| RecordedPlayer {
+                id: *id,
+                kind: self
+                    .kinds
+                    .get(id.seat() - 1)
+                    .map_or(name.to_string(), |kind| kind.to_string()),
+                name: name.to_string(),
+            })
+            .collect();
+        recorded_players.sort_by_key(|player| player.id);
+
+        let mut record = GameRecord::new(board.rows(), board.cols(), recorded_players);
+        record.date = Some(Local::now().date_naive());
+        record.turn_order = players.iter().map(|&(id, _)| id).collect();
+        record.setup = board.iter_owned_edges().collect();
+        self.record = Some(record);
+    }
+
+    fn turn_start(&mut self, _board: &Board, _turn: usize, _player_id: PlayerId, streak: usize) {
+        self.streak = streak;
+    }
+
+    fn edge_drawn(&mut self, _board: &Board, owned_edge: OwnedEdge) {
+        if let Some(record) = self.record.as_mut() {
+            record.moves.push(RecordedMove {
+                owned_edge,
+                streak: self.streak,
+            });
+        }
+    }
+
+    fn game_over(&mut self, _board: &Board, result: &WinnerResult) {
+        if let Some(record) = self.record.as_mut() {
+            record.result = Some(result.clone());
+        }
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_tag(f, "Game", GAME_TAG)?;
+        if let Some(date) = self.date {
+            write_tag(f, "Date", &date.format(DATE_FORMAT).to_string())?;
+        }
+        write_tag(f, "Size", &format!("{}x{}", self.rows, self.cols))?;
+        for player in self.players.iter() {
+            write_tag(f, &format!("Player{}", player.id.seat()), &player.kind)?;
+            write_tag(f, &format!("Name{}", player.id.seat()), &player.name)?;
+        }
+        write_tag(f, "Order", &format_seats(&self.turn_order))?;
+        if !self.setup.is_empty() {
+            let setup: Vec<String> = self.setup.iter().map(|&e| format_owned_edge(e)).collect();
+            write_tag(f, "Position", &setup.join(" "))?;
+        }
+        write_tag(f, "Result", &format_result(&self.result))?;
+        writeln!(f)?;
+
+        for (index, recorded) in self.moves.iter().enumerate() {
+            write!(
+                f,
+                "{}. {}",
+                index + 1,
+                format_owned_edge(recorded.owned_edge)
+            )?;
+            if 0 < recorded.streak {
+                write!(f, " +{}", recorded.streak)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = String;
+
+    /*
+     * Parses and validates a record. Unknown tags are skipped so that newer
+     * records can still be read.
+     */
+    fn from_str(text: &str) -> Result<GameRecord, String> {
+        let mut size = None;
+        let mut date = None;
+        let mut kinds: Vec<(PlayerId, String)> = vec![];
+        let mut names: Vec<(PlayerId, String)> = vec![];
+        let mut turn_order = None;
+        let mut setup = vec![];
+        let mut result_seats = None;
+        let mut moves = vec![];
+
+        for (line_index, raw_line) in text.lines().enumerate() {
+            let line = raw_line.trim();
+            let in_line = |err: String| format!("Line {}: {}", line_index + 1, err);
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                if !moves.is_empty() {
+                    return Err(in_line("Tags have to come before the moves".to_string()));
+                }
+                let (key, value) = parse_tag(line).map_err(in_line)?;
+                match key {
+                    "Size" => size = Some(parse_dimensions(&value).map_err(in_line)?),
+                    "Date" => {
+                        date =
+                            Some(NaiveDate::parse_from_str(&value, DATE_FORMAT).map_err(|_| {
+                                in_line(format!("Date {} doesn't make sense", value))
+                            })?)
+                    }
+                    "Order" => turn_order = Some(parse_seats(&value).map_err(in_line)?),
+                    "Position" => {
+                        setup = value
+                            .split_whitespace()
+                            .map(parse_owned_edge)
+                            .collect::<Result<_, _>>()
+                            .map_err(in_line)?
+                    }
+                    "Result" => result_seats = Some(parse_result(&value).map_err(in_line)?),
+                    _ => {
+                        if let Some(seat) = key.strip_prefix("Player") {
+                            kinds.push((parse_seat(seat).map_err(in_line)?, value.to_string()));
+                        } else if let Some(seat) = key.strip_prefix("Name") {
+                            names.push((parse_seat(seat).map_err(in_line)?, value.to_string()));
+                        }
+                    }
+                }
+                continue;
+            }
+
+            let recorded = parse_move(line, moves.len() + 1).map_err(in_line)?;
+            moves.push(recorded);
+        }
+
+        let (rows, cols) = size.ok_or("The record is missing its Size")?;
+        if kinds.is_empty() {
+            return Err("The record is missing its players".to_string());
+        }
+        kinds.sort();
+        let players: Vec<RecordedPlayer> = kinds
+            .into_iter()
+            .map(|(id, kind)| {
+                let name = names
+                    .iter()
+                    .find(|(name_id, _)| *name_id == id)
+                    .map_or(kind.clone(), |(_, name)| name.clone());
+                RecordedPlayer { id, kind, name }
+            })
+            .collect();
+
+        let mut record = GameRecord::new(rows, cols, players);
+        record.date = date;
+        record.setup = setup;
+        record.moves = moves;
+        if let Some(order) = turn_order {
+            let mut sorted_order = order.clone();
+            sorted_order.sort();
+            if sorted_order != record.players.iter().map(|p| p.id).collect::<Vec<_>>() {
+                return Err(format!(
+                    "Order {} does not match the players",
+                    format_seats(&order)
+                ));
+            }
+            record.turn_order = order;
+        }
+        if let Some(Some(seats)) = result_seats {
+            // The box counts come from the board, validate checks the seats.
+            let board = record.final_board()?;
+            let count = seats.first().map_or(0, |&id| board.owned_boxes_count(id));
+            record.result = Some(match seats.as_slice() {
+                [winner] => WinnerResult::Winner(*winner, count),
+                [] => WinnerResult::None,
+                _ => WinnerResult::Tie(seats, count),
+            });
+        }
+
+        record.validate()?;
+        Ok(record)
+    }
+}
+
+fn write_tag(f: &mut fmt::Formatter<'_>, key: &str, value: &str) -> fmt::Result {
+    writeln!(
+        f,
+        "[{} \"{}\"]",
+        key,
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+fn parse_tag(line: &str) -> Result<(&str, String), String> {
+    let invalid = || format!("Tag {} doesn't make sense", line);
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(invalid)?;
+    let (key, quoted) = inner.split_once(' ').ok_or_else(invalid)?;
+    let quoted = quoted
+        .trim()
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            value.push(chars.next().ok_or_else(invalid)?);
+        } else {
+            value.push(c);
+        }
+    }
+    Ok((key, value))
+}
+
+fn parse_move(line: &str, expected_number: usize) -> Result<RecordedMove, String> {
+    let invalid = || format!("Move {} doesn't make sense", line);
+    let mut parts = line.split_whitespace();
+    let number = parts
+        .next()
+        .and_then(|part| part.strip_suffix('.'))
+        .and_then(|number| number.parse::<usize>().ok())
+        .ok_or_else(invalid)?;
+    if number != expected_number {
+        return Err(format!(
+            "Expected move {} but found {}",
+            expected_number, number
+        ));
+    }
+    let owned_edge = parse_owned_edge(parts.next().ok_or_else(invalid)?)?;
+    let streak = match parts.next() {
+        None => 0,
+        Some(marker) => marker
+            .strip_prefix('+')
+            .and_then(|count| count.parse::<usize>().ok())
+            .ok_or_else(invalid)?,
+    };
+    if parts.next().is_some() {
+        return Err(invalid());
+    }
+    Ok(RecordedMove { owned_edge, streak })
+}
+
+fn format_result(result: &Option<WinnerResult>) -> String {
+    match result {
+        Some(WinnerResult::Winner(id, _)) => id.seat().to_string(),
+        Some(WinnerResult::Tie(ids, _)) => {
+            let mut sorted_ids = ids.clone();
+            sorted_ids.sort();
+            format_seats(&sorted_ids)
+        }
+        Some(WinnerResult::None) => "".to_string(),
+        None => "*".to_string(),
+    }
+}
+
+/* None means the game didn't finish, Some(vec![]) that nobody won. */
+fn parse_result(value: &str) -> Result<Option<Vec<PlayerId>>, String> {
+    match value {
+        "*" => Ok(None),
+        "" => Ok(Some(vec![])),
+        _ => parse_seats(value).map(Some),
+    }
+}
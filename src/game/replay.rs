@@ -0,0 +1,214 @@
+/*
+ * Steps through a saved game record move by move, showing the board and the
+ * same captions the engine prints while the game is live.
+ */
+use crate::game::board::Board;
+use crate::game::engine::{game_over_banner, winner_message};
+use crate::game::record::GameRecord;
+use crate::players::io::try_read_trimmed;
+use crate::players::player::PlayerId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayCommand {
+    Forward(usize),
+    Back(usize),
+    /* Jumps to the position after the given move, 0 being the empty board. */
+    Jump(usize),
+    Start,
+    End,
+    Help,
+    Quit,
+}
+
+pub const HELP: &str = "\
+Enter (or n) steps forward and b steps back, either can be followed by a count.
+A number jumps to the board after that move, s and e go to the start and end.
+q quits.";
+
+/* Reads a command as typed at the replay prompt, ex: "b 3" or "12". */
+pub fn parse_command(input: &str) -> Option<ReplayCommand> {
+    let mut parts = input.split_whitespace();
+    let first = match parts.next() {
+        None => return Some(ReplayCommand::Forward(1)),
+        Some(first) => first,
+    };
+    let count = match parts.next() {
+        None => 1,
+        Some(count_str) => count_str.parse::<usize>().ok()?,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    match first {
+        "n" | "f" => Some(ReplayCommand::Forward(count)),
+        "b" | "p" => Some(ReplayCommand::Back(count)),
+        "s" => Some(ReplayCommand::Start),
+        "e" => Some(ReplayCommand::End),
+        "h" | "?" => Some(ReplayCommand::Help),
+        "q" => Some(ReplayCommand::Quit),
+        _ => first.parse::<usize>().ok().map(ReplayCommand::Jump),
+    }
+}
+
+pub struct Replay {
+    record: GameRecord,
+    /* Every move is drawn and then undone, so stepping is just redo and undo. */
+    board: Board,
+    /* Edges that were drawn before the first move. */
+    setup_count: usize,
+}
+
+impl Replay {
+    pub fn new(record: GameRecord) -> Result<Replay, String> {
+        let mut board = record.final_board()?;
+        let setup_count = record.setup.len();
+        board.truncate_to(setup_count);
+        Ok(Replay {
+            record,
+            board,
+            setup_count,
+        })
+    }
+
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /* How many moves are drawn on the board right now. */
+    pub fn ply(&self) -> usize {
+        self.board.iter_owned_edges().count() - self.setup_count
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.ply() == self.record.ply_count()
+    }
+
+    /* Applies the command, returning false if it was Quit. */
+    pub fn apply(&mut self, command: ReplayCommand) -> bool {
+        match command {
+            ReplayCommand::Forward(count) => self.jump_to(self.ply() + count),
+            ReplayCommand::Back(count) => self.jump_to(self.ply().saturating_sub(count)),
+            ReplayCommand::Jump(ply) => self.jump_to(ply),
+            ReplayCommand::Start => self.jump_to(0),
+            ReplayCommand::End => self.jump_to(self.record.ply_count()),
+            ReplayCommand::Help => {}
+            ReplayCommand::Quit => return false,
+        }
+        true
+    }
+
+    /* Moves to the board after `ply` moves, stopping at the end of the game. */
+    pub fn jump_to(&mut self, ply: usize) {
+        let target = ply.min(self.record.ply_count());
+        self.board.truncate_to(self.setup_count + target);
+        while self.ply() < target {
+            self.board.redo();
+        }
+    }
+
+    /*
+     * What the engine printed around the most recent move:
+     *
+     *   Streak 1! Boox Two
+     *   Player Two drew: (1, 1)·─·(2, 1)
+     */
+    pub fn caption(&self) -> String {
+        let ply = self.ply();
+        if ply == 0 {
+            return format!(
+                "Playing with {}x{} squares ({}x{} dots)",
+                self.board.rows(),
+                self.board.cols(),
+                self.board.dot_rows(),
+                self.board.dot_cols()
+            );
+        }
+
+        let recorded = self.record.moves[ply - 1];
+        let (player_id, edge) = recorded.owned_edge;
+        let mut lines = vec![];
+        if recorded.streak == 0 {
+            lines.push(format!(
+                "Turn #{}: {}",
+                self.setup_count + ply,
+                self.name_of(player_id)
+            ));
+        } else {
+            lines.push(format!(
+                "Streak {}! {}",
+                recorded.streak,
+                self.name_of(player_id)
+            ));
+        }
+        lines.push(format!("Player {} drew: {}", player_id, edge));
+
+        let finished_box = self
+            .board
+            .associated_boxes(edge)
+            .into_iter()
+            .any(|dotbox| self.board.box_owner(dotbox.0) == Some(player_id));
+        if finished_box {
+            lines.push(format!("Player {} finished a box!", player_id));
+        }
+        lines.join("\n")
+    }
+
+    fn name_of(&self, id: PlayerId) -> String {
+        self.record
+            .players
+            .iter()
+            .find(|player| player.id == id)
+            .map_or(id.to_string(), |player| player.name.to_string())
+    }
+
+    /* The board and caption for the current move, with the result at the end. */
+    pub fn render(&self) -> String {
+        let mut rendered = format!(
+            "{}\n\n{}\n\nMove {} of {}",
+            self.caption(),
+            self.board.drawing(),
+            self.ply(),
+            self.record.ply_count()
+        );
+        if self.is_over() {
+            if let Some(result) = &self.record.result {
+                let message = winner_message(result, |id| self.name_of(id));
+                rendered = format!("{}\n\n{}", rendered, game_over_banner(&message));
+            }
+        }
+        rendered
+    }
+}
+
+/* Plays the record back, reading commands from stdin until Quit. */
+pub fn run_replay(record: GameRecord) -> Result<(), String> {
+    let mut replay = Replay::new(record)?;
+    println!("{}", HELP);
+
+    loop {
+        print!("\n\n{}\n\n", replay.render());
+        println!("Command (h for help):");
+
+        let command = loop {
+            let input = match try_read_trimmed() {
+                Some(input) => input,
+                None => return Ok(()),
+            };
+            match parse_command(&input) {
+                Some(command) => break command,
+                None => println!("{}", HELP),
+            }
+        };
+        if command == ReplayCommand::Help {
+            println!("{}", HELP);
+        }
+        if !replay.apply(command) {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::game::board::{dot, BoardSize, Dot, DotBox, Edge};
+
+/*
+ * The ways a board can be turned or flipped onto itself. Square boards have
+ * all eight, but rectangular boards only keep their shape under the four that
+ * don't swap rows with columns.
+ *
+ * Every transform is described by the board dimensions (in boxes) before it
+ * is applied.
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /* Clockwise quarter turn. */
+    Rotate90,
+    Rotate180,
+    /* Counter-clockwise quarter turn. */
+    Rotate270,
+    /* Mirror top to bottom. */
+    FlipRows,
+    /* Mirror left to right. */
+    FlipCols,
+    /* Mirror across the diagonal through the upper-left corner. */
+    Transpose,
+    /* Mirror across the diagonal through the upper-right corner. */
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipRows,
+        Symmetry::FlipCols,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /* The symmetries that map a board of these dimensions onto itself. */
+    pub fn for_dimensions(rows: BoardSize, cols: BoardSize) -> Vec<Symmetry> {
+        Self::ALL
+            .iter()
+            .copied()
+            .filter(|symmetry| rows == cols || !symmetry.swaps_dimensions())
+            .collect()
+    }
+
+    /* Whether the transformed board is cols x rows instead of rows x cols. */
+    pub fn swaps_dimensions(&self) -> bool {
+        match self {
+            Symmetry::Rotate90
+            | Symmetry::Rotate270
+            | Symmetry::Transpose
+            | Symmetry::AntiTranspose => true,
+            Symmetry::Identity | Symmetry::Rotate180 | Symmetry::FlipRows | Symmetry::FlipCols => {
+                false
+            }
+        }
+    }
+
+    /* The symmetry that undoes this one. */
+    pub fn inverse(&self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => *other,
+        }
+    }
+
+    /* The dimensions of a rows x cols board after the transform. */
+    pub fn dimensions(&self, rows: BoardSize, cols: BoardSize) -> (BoardSize, BoardSize) {
+        if self.swaps_dimensions() {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        }
+    }
+
+    pub fn transform_dot(&self, Dot { row, col }: Dot, rows: BoardSize, cols: BoardSize) -> Dot {
+        match self {
+            Symmetry::Identity => dot(row, col),
+            Symmetry::Rotate90 => dot(col, rows - row),
+            Symmetry::Rotate180 => dot(rows - row, cols - col),
+            Symmetry::Rotate270 => dot(cols - col, row),
+            Symmetry::FlipRows => dot(rows - row, col),
+            Symmetry::FlipCols => dot(row, cols - col),
+            Symmetry::Transpose => dot(col, row),
+            Symmetry::AntiTranspose => dot(cols - col, rows - row),
+        }
+    }
+
+    /* Transformed edges are always given with their upper or left dot first. */
+    pub fn transform_edge(&self, Edge(d1, d2): Edge, rows: BoardSize, cols: BoardSize) -> Edge {
+        let t1 = self.transform_dot(d1, rows, cols);
+        let t2 = self.transform_dot(d2, rows, cols);
+        Edge(t1.min(t2), t1.max(t2))
+    }
+
+    pub fn transform_box(&self, dotbox: DotBox, rows: BoardSize, cols: BoardSize) -> DotBox {
+        let corners = [
+            dotbox.upper_left(),
+            dotbox.upper_right(),
+            dotbox.lower_right(),
+            dotbox.lower_left(),
+        ];
+        let upper_left = corners
+            .iter()
+            .map(|&corner| self.transform_dot(corner, rows, cols))
+            .min()
+            .unwrap();
+        DotBox(upper_left)
+    }
+}
@@ -0,0 +1,392 @@
+/*
+ * Round robin tournaments between AI players. Every pair of players meets on
+ * every board size, taking turns going first, and the results are summed up
+ * in a crosstable.
+ */
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use crate::game::batch::{game_seed, play_in_parallel};
+use crate::game::board::{BoardSize, WinnerResult};
+use crate::game::engine::{play_headless, Opts};
+use crate::game::error::GameError;
+use crate::game::ratings::Ratings;
+use crate::players::choose::AI_KEYS;
+use crate::players::player::PlayerId;
+use crate::utils::pad_end;
+
+pub struct TournamentOpts {
+    /* Player types, each of which has to be an AI. */
+    pub players: Vec<String>,
+    /* Square boards, ex: 3 for 3x3 boxes. */
+    pub sizes: Vec<BoardSize>,
+    /* Games per pairing on each size. The first player alternates. */
+    pub games: usize,
+    /* Random safe edges to open every game with, see game::opening. */
+    pub random_opening: usize,
+    /* Every game gets its own seed worked out from this one. */
+    pub seed: u64,
+    /* The ratings file to update after every game, see game::ratings. */
+    pub ratings: Option<PathBuf>,
+    /* How many games to play at once, see game::batch. */
+    pub jobs: usize,
+}
+
+/* One game of the tournament, from the point of view of its seats. */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TournamentGame {
+    /* Indexes into the tournament's players, first mover first. */
+    pub players: [usize; 2],
+    pub size: BoardSize,
+    pub seed: u64,
+    pub boxes: [usize; 2],
+    /* The seat that won, None for a tie. */
+    pub winner: Option<usize>,
+    /* Whether the game ended with a forfeit rather than a full board. */
+    pub forfeit: bool,
+}
+
+impl TournamentGame {
+    /* Scores for game::ratings, where the winner beats the other player. */
+    pub fn scores(&self, players: &[String]) -> Vec<(String, i64)> {
+        (0..2)
+            .map(|seat| {
+                let won = self.winner == Some(seat);
+                (players[self.players[seat]].to_string(), won as i64)
+            })
+            .collect()
+    }
+}
+
+/* How one player did against another over all their games. */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PairingScore {
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub ties: usize,
+    /* Boxes won minus boxes lost, over every game. */
+    pub margin: i64,
+}
+
+impl PairingScore {
+    pub fn average_margin(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.margin as f64 / self.games as f64
+    }
+
+    fn add(&mut self, other: PairingScore) {
+        self.games += other.games;
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.ties += other.ties;
+        self.margin += other.margin;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tournament {
+    pub players: Vec<String>,
+    pub games: Vec<TournamentGame>,
+}
+
+/* Every game of the tournament in the order they are played, not yet played. */
+pub fn schedule(opts: &TournamentOpts) -> Vec<TournamentGame> {
+    let mut games = vec![];
+    for first in 0..opts.players.len() {
+        for second in (first + 1)..opts.players.len() {
+            for &size in opts.sizes.iter() {
+                for round in 0..opts.games {
+                    let players = if round % 2 == 0 {
+                        [first, second]
+                    } else {
+                        [second, first]
+                    };
+                    games.push(TournamentGame {
+                        players,
+                        size,
+                        seed: game_seed(opts.seed, games.len()),
+                        boxes: [0, 0],
+                        winner: None,
+                        forfeit: false,
+                    });
+                }
+            }
+        }
+    }
+    games
+}
+
+/* Checks the options make a tournament that can be played headless. */
+pub fn validate(opts: &TournamentOpts) -> Result<(), GameError> {
+    if opts.players.len() < 2 {
+        return Err(GameError::Setup(
+            "A tournament needs at least 2 players".to_string(),
+        ));
+    }
+    for (index, player) in opts.players.iter().enumerate() {
+        if !AI_KEYS.contains(&player.as_str()) {
+            return Err(GameError::Setup(format!(
+                "Player {} can't play in a tournament, try one of: {}",
+                player,
+                AI_KEYS.join(", ")
+            )));
+        }
+        if opts.players[..index].contains(player) {
+            return Err(GameError::Setup(format!(
+                "Player {} is in the tournament twice",
+                player
+            )));
+        }
+    }
+    if opts.sizes.is_empty() || opts.games == 0 {
+        return Err(GameError::Setup(
+            "A tournament needs at least one board size and game".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/* Plays a single scheduled game, filling in how it went. */
+pub fn play_tournament_game(
+    opts: &TournamentOpts,
+    game: &TournamentGame,
+) -> Result<TournamentGame, GameError> {
+    let outcome = play_headless(&Opts {
+        rows: game.size,
+        cols: game.size,
+        players: game
+            .players
+            .iter()
+            .map(|&index| opts.players[index].clone())
+            .collect(),
+        quiet: true,
+        random_opening: opts.random_opening,
+        seed: game.seed,
+        ..Opts::default()
+    })?;
+
+    let seat_of = |id: PlayerId| id.seat() - 1;
+    Ok(TournamentGame {
+        boxes: [
+            outcome.boxes_for(PlayerId::One),
+            outcome.boxes_for(PlayerId::Two),
+        ],
+        winner: match outcome.result {
+            WinnerResult::Winner(id, _) => Some(seat_of(id)),
+            _ => None,
+        },
+        forfeit: outcome.forfeit.is_some(),
+        ..game.clone()
+    })
+}
+
+/*
+ * Plays every game of the tournament, `jobs` at a time. The ratings are
+ * updated in the order the games were scheduled, whichever finishes first.
+ */
+pub fn run_tournament(opts: &TournamentOpts) -> Result<Tournament, GameError> {
+    validate(opts)?;
+    let mut ratings = match &opts.ratings {
+        Some(path) => Some(Ratings::load(path)?),
+        None => None,
+    };
+
+    let scheduled = schedule(opts);
+    let played = play_in_parallel(
+        scheduled.len(),
+        opts.jobs,
+        |index| play_tournament_game(opts, &scheduled[index]),
+        |_, played| {
+            if let (Some(ratings), Ok(game)) = (ratings.as_mut(), played) {
+                ratings.record_game(&game.scores(&opts.players));
+            }
+        },
+    );
+
+    if let (Some(path), Some(ratings)) = (&opts.ratings, &ratings) {
+        ratings.save(path)?;
+    }
+    Ok(Tournament {
+        players: opts.players.clone(),
+        games: played.into_iter().collect::<Result<_, _>>()?,
+    })
+}
+
+impl Tournament {
+    /* How `player` did against `opponent`, both indexes into the players. */
+    pub fn score(&self, player: usize, opponent: usize) -> PairingScore {
+        let mut score = PairingScore::default();
+        for game in self.games.iter() {
+            let seat = match game.players {
+                [first, second] if first == player && second == opponent => 0,
+                [first, second] if first == opponent && second == player => 1,
+                _ => continue,
+            };
+            score.games += 1;
+            match game.winner {
+                Some(winner) if winner == seat => score.wins += 1,
+                Some(_) => score.losses += 1,
+                None => score.ties += 1,
+            }
+            score.margin += game.boxes[seat] as i64 - game.boxes[1 - seat] as i64;
+        }
+        score
+    }
+
+    /* How the player did against everyone. */
+    pub fn total(&self, player: usize) -> PairingScore {
+        let mut total = PairingScore::default();
+        for opponent in 0..self.players.len() {
+            if opponent != player {
+                total.add(self.score(player, opponent));
+            }
+        }
+        total
+    }
+
+    /*
+     * Every player's results against every other, as wins-losses-ties and the
+     * average box margin:
+     *
+     *   Player     doot           boox           Total
+     *   doot       ·              4-0-0 +3.50    4-0-0 +3.50
+     *   boox       0-4-0 -3.50    ·              0-4-0 -3.50
+     */
+    pub fn crosstable(&self) -> String {
+        let width = self
+            .players
+            .iter()
+            .map(|player| player.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(14)
+            + 1;
+        let mut header = pad_end("Player", " ", width);
+        for player in self.players.iter() {
+            header.push_str(&pad_end(player, " ", width));
+        }
+        header.push_str("Total");
+
+        let mut lines = vec![header.trim_end().to_string()];
+        for (index, player) in self.players.iter().enumerate() {
+            let mut line = pad_end(player, " ", width);
+            for opponent in 0..self.players.len() {
+                let cell = if opponent == index {
+                    "·".to_string()
+                } else {
+                    format_score(&self.score(index, opponent))
+                };
+                line.push_str(&pad_end(&cell, " ", width));
+            }
+            line.push_str(&format_score(&self.total(index)));
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+
+    /* Every pairing both ways round, one per line. */
+    pub fn to_csv(&self) -> String {
+        let mut csv = "player,opponent,games,wins,losses,ties,average_margin\n".to_string();
+        for (player, opponent, score) in self.pairings() {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{:.2}",
+                player,
+                opponent,
+                score.games,
+                score.wins,
+                score.losses,
+                score.ties,
+                score.average_margin()
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    /* The same pairings as to_csv, along with every game that was played. */
+    pub fn to_json(&self) -> String {
+        let players: Vec<String> = self.players.iter().map(|p| json_string(p)).collect();
+        let pairings: Vec<String> = self
+            .pairings()
+            .map(|(player, opponent, score)| {
+                format!(
+                    "{{\"player\": {}, \"opponent\": {}, \"games\": {}, \"wins\": {}, \"losses\": {}, \"ties\": {}, \"average_margin\": {:.2}}}",
+                    json_string(player),
+                    json_string(opponent),
+                    score.games,
+                    score.wins,
+                    score.losses,
+                    score.ties,
+                    score.average_margin()
+                )
+            })
+            .collect();
+        let games: Vec<String> = self
+            .games
+            .iter()
+            .map(|game| {
+                format!(
+                    "{{\"first\": {}, \"second\": {}, \"size\": {}, \"seed\": {}, \"boxes\": [{}, {}], \"winner\": {}, \"forfeit\": {}}}",
+                    json_string(&self.players[game.players[0]]),
+                    json_string(&self.players[game.players[1]]),
+                    game.size,
+                    game.seed,
+                    game.boxes[0],
+                    game.boxes[1],
+                    game.winner
+                        .map_or("null".to_string(), |seat| json_string(&self.players[game.players[seat]])),
+                    game.forfeit
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"players\": [{}],\n  \"pairings\": [\n    {}\n  ],\n  \"games\": [\n    {}\n  ]\n}}\n",
+            players.join(", "),
+            pairings.join(",\n    "),
+            games.join(",\n    ")
+        )
+    }
+
+    fn pairings(&self) -> impl Iterator<Item = (&String, &String, PairingScore)> + '_ {
+        (0..self.players.len()).flat_map(move |player| {
+            (0..self.players.len())
+                .filter(move |&opponent| opponent != player)
+                .map(move |opponent| {
+                    (
+                        &self.players[player],
+                        &self.players[opponent],
+                        self.score(player, opponent),
+                    )
+                })
+        })
+    }
+}
+
+fn format_score(score: &PairingScore) -> String {
+    format!(
+        "{}-{}-{} {:+.2}",
+        score.wins,
+        score.losses,
+        score.ties,
+        score.average_margin()
+    )
+}
+
+/* Quotes a string for JSON. Player types are plain, but just in case. */
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
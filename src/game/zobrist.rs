@@ -0,0 +1,30 @@
+/*
+ * Keys for Zobrist hashing of board positions. A position hashes to the XOR of
+ * the keys of everything in it, so the hash can be updated one draw at a time
+ * and doesn't depend on the order the edges were drawn in.
+ *
+ * Keys are derived from their index instead of being stored in a table, so
+ * they are the same for every board, process and run.
+ */
+use crate::game::board::BoardSize;
+use crate::players::player::PlayerId;
+use crate::utils::splitmix64;
+
+const DIMENSIONS_SEED: u64 = 0xD075_D1E5_0000_0000;
+const EDGE_SEED: u64 = 0xD075_ED6E_0000_0000;
+const BOX_SEED: u64 = 0xD075_B0C5_0000_0000;
+
+/* The starting hash of an empty board, so that different shapes don't collide. */
+pub fn dimensions_key(rows: BoardSize, cols: BoardSize) -> u64 {
+    splitmix64(DIMENSIONS_SEED ^ ((rows as u64) << 16) ^ (cols as u64))
+}
+
+/* The key for a drawn edge, by Board::edge_index. */
+pub fn edge_key(edge_index: usize) -> u64 {
+    splitmix64(EDGE_SEED ^ (edge_index as u64))
+}
+
+/* The key for a completed box, by Board::box_index, and the player who owns it. */
+pub fn box_key(box_index: usize, owner: PlayerId) -> u64 {
+    splitmix64(BOX_SEED ^ ((box_index as u64) << 8) ^ (owner.seat() as u64))
+}
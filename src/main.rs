@@ -1,22 +1,51 @@
-use clap::{App, Arg};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::{App, Arg, ArgMatches, SubCommand};
 use textwrap::dedent as dd;
 
-use doots::game::board::BoardSize;
-use doots::game::engine::{run_game, Opts};
+use doots::game::batch::{play_batch, summarize};
+use doots::game::board::{Board, BoardSize, MAX_SIZE, MIN_SIZE};
+use doots::game::engine::{run_game, Opts, TimeoutPolicy};
+use doots::game::error::GameError;
+use doots::game::notation::parse_seat;
+use doots::game::ratings::Ratings;
+use doots::game::record::GameRecord;
+use doots::game::replay::run_replay;
+use doots::game::tournament::{run_tournament, TournamentOpts};
+use doots::players::player::PlayerId;
+use doots::utils::splitmix64;
 
 fn main() {
     match cli() {
         Ok(_) => {}
-        Err(msg) => eprintln!("{}", msg),
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(exit_code(&err));
+        }
+    }
+}
+
+/* Lets scripts tell apart a bad command from a game gone wrong. */
+fn exit_code(err: &GameError) -> i32 {
+    match err {
+        GameError::Setup(_) => 2,
+        GameError::InvalidMove { .. } => 3,
+        GameError::Record(_) => 4,
+        GameError::TimedOut { .. } => 5,
+        GameError::Panicked { .. } => 6,
+        GameError::Ratings(_) => 7,
     }
 }
 
 const SIZE_DEFAULT: BoardSize = 10;
 const SIZE_DEFAULT_STR: &str = "10";
-const SIZE_MIN: BoardSize = 1;
-const SIZE_MAX: BoardSize = 100;
+const SIZE_MIN: BoardSize = MIN_SIZE;
+const SIZE_MAX: BoardSize = MAX_SIZE;
 
-fn cli() -> Result<(), String> {
+fn cli() -> Result<(), GameError> {
     let matches = App::new("doots")
         .arg(
             Arg::with_name("size")
@@ -30,6 +59,20 @@ fn cli() -> Result<(), String> {
                         size 2 => 2x2 grid => 9 dots
                     ")),
         )
+        .arg(
+            Arg::with_name("rows")
+                .short("r")
+                .long("rows")
+                .takes_value(true)
+                .help("How many boxes tall the game is. Overrides --size."),
+        )
+        .arg(
+            Arg::with_name("cols")
+                .short("c")
+                .long("cols")
+                .takes_value(true)
+                .help("How many boxes wide the game is. Overrides --size."),
+        )
         .arg(
             Arg::with_name("player_one")
                 .short("1")
@@ -46,16 +89,460 @@ fn cli() -> Result<(), String> {
                 .default_value("hoomin")
                 .help("Player two type"),
         )
+        .arg(
+            Arg::with_name("player")
+                .short("p")
+                .long("player")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .conflicts_with_all(&["player_one", "player_two"])
+                .help(&dd("
+                    Player type for the next seat. Repeat it for every player
+                    in the game, ex: -p doot -p boox -p sleepy
+                    ")),
+        )
+        .arg(
+            Arg::with_name("order")
+                .short("o")
+                .long("order")
+                .takes_value(true)
+                .help(&dd("
+                    Comma separated seats in the order they take turns, ex:
+                        3,1,2 => seat 3 goes first, then 1, then 2
+                    ")),
+        )
         .arg(
             Arg::with_name("quiet")
                 .short("q")
                 .long("quiet")
                 .help("In quiet mode the board is not printed until the end."),
         )
+        .arg(
+            Arg::with_name("record")
+                .long("record")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Saves a record of the game to the file when it's over."),
+        )
+        .arg(
+            Arg::with_name("ratings")
+                .long("ratings")
+                .takes_value(true)
+                .value_name("FILE")
+                .help(&dd("
+                    Updates the players' ratings in the file when the game is
+                    over. Only games where every player is an AI are rated.
+                    ")),
+        )
+        .arg(
+            Arg::with_name("games")
+                .long("games")
+                .takes_value(true)
+                .value_name("N")
+                .conflicts_with("record")
+                .help(&dd("
+                    Plays N games between AIs without showing them, each with
+                    its own seed, and sums them up.
+                    ")),
+        )
+        .arg(
+            Arg::with_name("jobs")
+                .long("jobs")
+                .short("j")
+                .takes_value(true)
+                .value_name("N")
+                .default_value("1")
+                .help("How many games to play at once with --games."),
+        )
+        .arg(
+            Arg::with_name("move_time")
+                .long("move-time")
+                .takes_value(true)
+                .value_name("TIME")
+                .help(&dd("
+                    How long each player gets per move, ex: 500ms, 2s or 1.5
+                    (seconds). Unlimited by default.
+                    ")),
+        )
+        .arg(
+            Arg::with_name("game_time")
+                .long("game-time")
+                .takes_value(true)
+                .value_name("TIME")
+                .help("How long each player gets for the whole game, like --move-time."),
+        )
+        .arg(
+            Arg::with_name("on_timeout")
+                .long("on-timeout")
+                .takes_value(true)
+                .possible_values(&["forfeit", "fallback"])
+                .default_value("forfeit")
+                .help(&dd("
+                    What happens to a player who runs out of time:
+                        forfeit => they lose the game
+                        fallback => the engine draws a safe edge for them
+                    ")),
+        )
+        .arg(
+            Arg::with_name("position")
+                .long("position")
+                .takes_value(true)
+                .value_name("FILE|NOTATION")
+                .help(&dd("
+                    Starts the game with edges already drawn, from a file or
+                    written out, ex: \"2x2 1:0,0h 2:1,1v\". The position's size
+                    overrides --size, --rows and --cols.
+                    ")),
+        )
+        .arg(
+            Arg::with_name("to_move")
+                .long("to-move")
+                .takes_value(true)
+                .value_name("SEAT")
+                .help("The seat that moves first. Defaults to the first in the turn order."),
+        )
+        .arg(
+            Arg::with_name("random_opening")
+                .long("random-opening")
+                .takes_value(true)
+                .value_name("N")
+                .help(&dd("
+                    Draws N random edges that don't give away a box before the
+                    players take over, so games between bots don't all play out
+                    the same.
+                    ")),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .value_name("S")
+                .help("Picks the random opening. Random unless given."),
+        )
+        .subcommand(
+            SubCommand::with_name("replay")
+                .about("Steps through a game saved with --record")
+                .arg(
+                    Arg::with_name("file")
+                        .required(true)
+                        .help("The game record to replay"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("tournament")
+                .about("Plays every AI against every other and tallies the results")
+                .arg(
+                    Arg::with_name("players")
+                        .required(true)
+                        .multiple(true)
+                        .min_values(2)
+                        .help("The AI player types to enter, ex: doot boox"),
+                )
+                .arg(
+                    Arg::with_name("sizes")
+                        .long("sizes")
+                        .takes_value(true)
+                        .default_value("3")
+                        .help("Comma separated board sizes to play on, ex: 3,5"),
+                )
+                .arg(
+                    Arg::with_name("games")
+                        .long("games")
+                        .takes_value(true)
+                        .default_value("2")
+                        .help(&dd("
+                            Games per pairing on each size. Players take turns
+                            going first.
+                            ")),
+                )
+                .arg(
+                    Arg::with_name("random_opening")
+                        .long("random-opening")
+                        .takes_value(true)
+                        .value_name("N")
+                        .help("Random edges to start every game with, like for a single game."),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .value_name("S")
+                        .help("Picks the random openings. Random unless given."),
+                )
+                .arg(
+                    Arg::with_name("ratings")
+                        .long("ratings")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Updates the players' ratings in the file after every game."),
+                )
+                .arg(
+                    Arg::with_name("jobs")
+                        .long("jobs")
+                        .short("j")
+                        .takes_value(true)
+                        .value_name("N")
+                        .default_value("1")
+                        .help("How many games to play at once."),
+                )
+                .arg(
+                    Arg::with_name("csv")
+                        .long("csv")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Saves the results of every pairing as CSV."),
+                )
+                .arg(
+                    Arg::with_name("json")
+                        .long("json")
+                        .takes_value(true)
+                        .value_name("FILE")
+                        .help("Saves the results of every pairing and game as JSON."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("ratings")
+                .about("Shows the ratings saved with --ratings")
+                .arg(
+                    Arg::with_name("file")
+                        .required(true)
+                        .help("The ratings file to show"),
+                ),
+        )
         .get_matches();
 
-    let size_str = matches.value_of("size").unwrap().trim();
-    let board_size = match size_str.parse::<BoardSize>() {
+    if let Some(replay_matches) = matches.subcommand_matches("replay") {
+        return replay(replay_matches);
+    }
+    if let Some(ratings_matches) = matches.subcommand_matches("ratings") {
+        let ratings = Ratings::load(Path::new(ratings_matches.value_of("file").unwrap()))?;
+        println!("{}", ratings.table());
+        return Ok(());
+    }
+    if let Some(tournament_matches) = matches.subcommand_matches("tournament") {
+        return tournament(tournament_matches);
+    }
+
+    let board_size = parse_size(matches.value_of("size").unwrap());
+    let rows = matches.value_of("rows").map_or(board_size, parse_size);
+    let cols = matches.value_of("cols").map_or(board_size, parse_size);
+
+    let quiet = matches.occurrences_of("quiet") > 0;
+
+    let players: Vec<String> = match matches.values_of("player") {
+        Some(values) => values.map(|v| v.to_string()).collect(),
+        None => vec![
+            matches.value_of("player_one").unwrap().to_string(),
+            matches.value_of("player_two").unwrap().to_string(),
+        ],
+    };
+
+    let turn_order = match matches.value_of("order") {
+        Some(order_str) => parse_order(order_str).map_err(GameError::Setup)?,
+        None => vec![],
+    };
+
+    let move_time = match matches.value_of("move_time") {
+        Some(time_str) => Some(parse_duration(time_str).map_err(GameError::Setup)?),
+        None => None,
+    };
+    let game_time = match matches.value_of("game_time") {
+        Some(time_str) => Some(parse_duration(time_str).map_err(GameError::Setup)?),
+        None => None,
+    };
+    let position = match matches.value_of("position") {
+        Some(position_arg) => Some(parse_position(position_arg).map_err(GameError::Setup)?),
+        None => None,
+    };
+    let to_move = match matches.value_of("to_move") {
+        Some(seat_str) => Some(parse_seat(seat_str.trim()).map_err(GameError::Setup)?),
+        None => None,
+    };
+    let random_opening = parse_random_opening(&matches)?;
+    let seed = parse_seed(&matches, random_opening)?;
+    let on_timeout = match matches.value_of("on_timeout") {
+        Some("fallback") => TimeoutPolicy::Fallback,
+        _ => TimeoutPolicy::Forfeit,
+    };
+
+    let opts = Opts {
+        rows,
+        cols,
+        players,
+        turn_order,
+        quiet,
+        record: matches.value_of("record").map(PathBuf::from),
+        ratings: matches.value_of("ratings").map(PathBuf::from),
+        move_time,
+        game_time,
+        on_timeout,
+        position,
+        to_move,
+        random_opening,
+        seed,
+        ..Opts::default()
+    };
+    match matches.value_of("games") {
+        Some(games_str) => batch(
+            &opts,
+            parse_count("Games", games_str)?,
+            parse_jobs(&matches)?,
+        ),
+        None => run_game(&opts),
+    }
+}
+
+fn batch(opts: &Opts, games: usize, jobs: usize) -> Result<(), GameError> {
+    let outcomes = play_batch(opts, games, jobs)?;
+    println!("{}", summarize(&opts.players, &outcomes));
+    Ok(())
+}
+
+fn replay(matches: &ArgMatches) -> Result<(), GameError> {
+    let path = matches.value_of("file").unwrap();
+    let text = fs::read_to_string(path).map_err(|err| {
+        GameError::Record(format!("Couldn't read the game record {}: {}", path, err))
+    })?;
+    let record = text.parse::<GameRecord>().map_err(GameError::Record)?;
+    run_replay(record).map_err(GameError::Record)
+}
+
+fn tournament(matches: &ArgMatches) -> Result<(), GameError> {
+    let sizes = matches
+        .value_of("sizes")
+        .unwrap()
+        .split(',')
+        .map(parse_size)
+        .collect();
+    let games = parse_count("Games", matches.value_of("games").unwrap())?;
+    let random_opening = parse_random_opening(matches)?;
+    let opts = TournamentOpts {
+        players: matches
+            .values_of("players")
+            .unwrap()
+            .map(|v| v.to_string())
+            .collect(),
+        sizes,
+        games,
+        random_opening,
+        seed: parse_seed(matches, random_opening)?,
+        ratings: matches.value_of("ratings").map(PathBuf::from),
+        jobs: parse_jobs(matches)?,
+    };
+
+    let results = run_tournament(&opts)?;
+    println!("{}", results.crosstable());
+    if let Some(path) = matches.value_of("csv") {
+        save_results(path, &results.to_csv())?;
+    }
+    if let Some(path) = matches.value_of("json") {
+        save_results(path, &results.to_json())?;
+    }
+    Ok(())
+}
+
+fn save_results(path: &str, text: &str) -> Result<(), GameError> {
+    fs::write(path, text)
+        .map_err(|err| GameError::Setup(format!("Couldn't save the results to {}: {}", path, err)))
+}
+
+fn parse_random_opening(matches: &ArgMatches) -> Result<usize, GameError> {
+    match matches.value_of("random_opening") {
+        Some(count_str) => parse_count("Random opening", count_str),
+        None => Ok(0),
+    }
+}
+
+fn parse_jobs(matches: &ArgMatches) -> Result<usize, GameError> {
+    match parse_count("Jobs", matches.value_of("jobs").unwrap())? {
+        0 => Err(GameError::Setup("Jobs 0 doesn't make sense.".to_string())),
+        jobs => Ok(jobs),
+    }
+}
+
+/* Reads a number of something, naming it in the error. */
+fn parse_count(what: &str, count_str: &str) -> Result<usize, GameError> {
+    count_str
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| GameError::Setup(format!("{} {} doesn't make sense.", what, count_str)))
+}
+
+/*
+ * Reads the seed, or picks one when there is none. A picked seed is shown if
+ * there's a random opening, so the games can be played again.
+ */
+fn parse_seed(matches: &ArgMatches, random_opening: usize) -> Result<u64, GameError> {
+    match matches.value_of("seed") {
+        Some(seed_str) => seed_str
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| GameError::Setup(format!("Seed {} doesn't make sense.", seed_str))),
+        None => {
+            let seed = random_seed();
+            if 0 < random_opening {
+                eprintln!("Using seed {} for the random opening.", seed);
+            }
+            Ok(seed)
+        }
+    }
+}
+
+fn parse_order(order_arg: &str) -> Result<Vec<PlayerId>, String> {
+    order_arg
+        .split(',')
+        .map(|seat_str| {
+            seat_str
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(PlayerId::from_seat)
+                .ok_or(format!("Seat {} doesn't make sense.", seat_str))
+        })
+        .collect()
+}
+
+/* Reads a position in board notation, or the file it was saved to. */
+fn parse_position(position_arg: &str) -> Result<Board, String> {
+    let path = Path::new(position_arg);
+    let notation = if path.is_file() {
+        fs::read_to_string(path)
+            .map_err(|err| format!("Couldn't read the position {}: {}", position_arg, err))?
+    } else {
+        position_arg.to_string()
+    };
+    notation.parse::<Board>()
+}
+
+/* A seed for when none is given, so every game gets a different opening. */
+fn random_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| splitmix64(since.as_nanos() as u64))
+}
+
+/* Reads a time like 250ms, 3s or 1.5 (seconds). */
+fn parse_duration(time_arg: &str) -> Result<Duration, String> {
+    let time_str = time_arg.trim();
+    let (number_str, scale) = if let Some(millis) = time_str.strip_suffix("ms") {
+        (millis, 0.001)
+    } else if let Some(secs) = time_str.strip_suffix('s') {
+        (secs, 1.0)
+    } else {
+        (time_str, 1.0)
+    };
+    match number_str.parse::<f64>() {
+        Ok(number) if 0.0 < number && number.is_finite() => {
+            Ok(Duration::from_secs_f64(number * scale))
+        }
+        _ => Err(format!("Time {} doesn't make sense.", time_str)),
+    }
+}
+
+fn parse_size(size_arg: &str) -> BoardSize {
+    let size_str = size_arg.trim();
+    match size_str.parse::<BoardSize>() {
         Err(_) => {
             eprintln!(
                 "Size {} doesn't make sense so I'm going to use {}.",
@@ -81,17 +568,5 @@ fn cli() -> Result<(), String> {
                 size_parsed
             }
         }
-    };
-
-    let quiet = matches.occurrences_of("quiet") > 0;
-
-    let player_one = matches.value_of("player_one").unwrap().to_string();
-    let player_two = matches.value_of("player_two").unwrap().to_string();
-
-    run_game(&Opts {
-        board_size,
-        player_one,
-        player_two,
-        quiet,
-    })
+    }
 }
@@ -1,36 +1,70 @@
+use std::sync::Arc;
+
 use crate::players::hoomin;
-use crate::players::player::{Player, PlayerId};
+use crate::players::player::{Player, PlayerId, SeatedPlayer};
 
 use crate::ai::boox;
 use crate::ai::doot;
 use crate::ai::sleepy;
 
+pub const MIN_PLAYERS: usize = 2;
+pub const MAX_PLAYERS: usize = PlayerId::ALL.len();
+
+/* The player types that play without anyone at the keyboard. */
+pub const AI_KEYS: [&str; 3] = [doot::KEY, boox::KEY, sleepy::KEY];
+
 /*
- * Constructs the players for a game from the known Player types. Any name that
- * doesn't
+ * Makes a fresh player for the seat it's given. Games get their players from
+ * factories rather than sharing live ones, so games on different threads
+ * never touch the same player.
  */
-pub fn choose(name_one: &str, name_two: &str) -> Vec<(PlayerId, Box<dyn Player>)> {
-    let mut players: Vec<(PlayerId, Box<dyn Player>)> = Vec::new();
-
-    for &(name, id) in [(name_one, PlayerId::One), (name_two, PlayerId::Two)].iter() {
-        match name {
-            doot::KEY => {
-                players.push((id, Box::new(doot::AI::new(id))));
-            }
-            boox::KEY => {
-                players.push((id, Box::new(boox::AI::new(id))));
-            }
-            sleepy::KEY => {
-                players.push((id, Box::new(sleepy::AI::new(id))));
-            }
-            _ => {
-                players.push((id, Box::new(hoomin::Hoomin::named(id, name.to_string()))));
-            }
+pub type PlayerFactory = Arc<dyn Fn(PlayerId) -> Box<dyn Player> + Send + Sync>;
+
+/*
+ * The factory for a known Player type. Any name that doesn't match one makes
+ * Hoomins by that name.
+ */
+pub fn factory(name: &str) -> PlayerFactory {
+    match name {
+        doot::KEY => Arc::new(|id| -> Box<dyn Player> { Box::new(doot::AI::new(id)) }),
+        boox::KEY => Arc::new(|id| -> Box<dyn Player> { Box::new(boox::AI::new(id)) }),
+        sleepy::KEY => Arc::new(|id| -> Box<dyn Player> { Box::new(sleepy::AI::new(id)) }),
+        hoomin::KEY => Arc::new(|id| -> Box<dyn Player> { Box::new(hoomin::Hoomin::new(id)) }),
+        _ => {
+            let name = name.to_string();
+            Arc::new(move |_| -> Box<dyn Player> { Box::new(hoomin::Hoomin::named(name.clone())) })
         }
     }
+}
 
-    if players.len() != 2 {
-        panic!("Unable to choose players for: {}, {}", name_one, name_two);
+/* The factories for a game's players, in seat order. */
+pub fn factories(names: &[String]) -> Result<Vec<PlayerFactory>, String> {
+    if names.len() < MIN_PLAYERS || MAX_PLAYERS < names.len() {
+        return Err(format!(
+            "Games need {} to {} players but {} were chosen: {}",
+            MIN_PLAYERS,
+            MAX_PLAYERS,
+            names.len(),
+            names.join(", ")
+        ));
     }
-    players
+    Ok(names.iter().map(|name| factory(name)).collect())
+}
+
+/* Makes a player from every factory, seating them in the order given. */
+pub fn seat(factories: &[PlayerFactory]) -> Vec<SeatedPlayer> {
+    factories
+        .iter()
+        .zip(PlayerId::ALL.iter())
+        .map(|(factory, &id)| (id, factory(id)))
+        .collect()
+}
+
+/*
+ * Constructs the players for a game from the known Player types, seating them
+ * in the order given. Any name that doesn't match a known type becomes the
+ * name of a Hoomin.
+ */
+pub fn choose(names: &[String]) -> Result<Vec<SeatedPlayer>, String> {
+    Ok(seat(&factories(names)?))
 }
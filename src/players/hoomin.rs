@@ -1,28 +1,23 @@
 use crate::game::board::{Board, Edge};
 use crate::players::io::read_dot;
-use crate::players::player::{Player, PlayerId};
+use crate::players::player::{Player, PlayerId, TurnContext};
 
 pub const KEY: &str = "hoomin";
 
 #[derive(Debug, Clone)]
 pub struct Hoomin {
-    id: PlayerId,
     name: String,
 }
 
 impl Hoomin {
     pub fn new(id: PlayerId) -> Self {
         Hoomin {
-            id,
-            name: match id {
-                PlayerId::One => "Hoomin One".to_string(),
-                PlayerId::Two => "Hoomin Two".to_string(),
-            },
+            name: format!("Hoomin {}", id),
         }
     }
 
-    pub fn named(id: PlayerId, name: String) -> Self {
-        Self { id, name }
+    pub fn named(name: String) -> Self {
+        Self { name }
     }
 }
 
@@ -31,6 +26,17 @@ impl Player for Hoomin {
         self.name.to_string()
     }
 
+    fn play_turn(&self, context: TurnContext) -> Edge {
+        if let Some(time_left) = context.time_left {
+            println!("You have {:.1} seconds left.", time_left.as_secs_f64());
+        }
+        self.play(context.board)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
     fn play(&self, board: Board) -> Edge {
         loop {
             println!("Draw an edge (row, col) -> (row, col):");
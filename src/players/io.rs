@@ -43,3 +43,12 @@ pub fn read_trimmed() -> String {
         .expect("Failed to read line");
     response.trim().to_string()
 }
+
+/* Like read_trimmed, but None once stdin has run out. */
+pub fn try_read_trimmed() -> Option<String> {
+    let mut response = String::new();
+    match io::stdin().read_line(&mut response) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(response.trim().to_string()),
+    }
+}
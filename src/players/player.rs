@@ -1,10 +1,111 @@
 use core::fmt;
+use std::time::Duration;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use crate::game::board::{Board, Edge};
+use crate::game::clock::{Clock, SystemClock};
+use crate::game::observer::RosterEntry;
+
+/* A player along with the seat they were given at the start of the game. */
+pub type SeatedPlayer = (PlayerId, Box<dyn Player>);
 
-pub trait Player {
+/*
+ * Players have to be shareable across threads because the engine moves AI
+ * players onto a worker thread when the game has a time limit.
+ */
+pub trait Player: Send + Sync {
     fn name(&self) -> String;
     fn play(&self, board: Board) -> Edge;
+
+    /*
+     * What the engine actually calls. Players that only need the board can
+     * stick to play, which this hands the board to by default.
+     */
+    fn play_turn(&self, context: TurnContext) -> Edge {
+        self.play(context.board)
+    }
+
+    /*
+     * Whether a person at the keyboard is playing, who can't be interrupted
+     * when time runs out.
+     */
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/* Everything a player gets to know when it's their turn. */
+#[derive(Clone, Debug)]
+pub struct TurnContext {
+    /* A copy of the official board, so go ahead and scribble on it. */
+    pub board: Board,
+    /* The seat the engine is asking, whatever the player thinks it is. */
+    pub player_id: PlayerId,
+    /* How many edges were drawn before this move. */
+    pub turn: usize,
+    /*
+     * How many moves the player has made since their turn started, each of
+     * which finished at least one box. A double box counts once.
+     */
+    pub streak: usize,
+    /* Boxes owned by each player, in turn order. */
+    pub scores: Vec<(PlayerId, usize)>,
+    /* Every player's seat and name, in turn order. */
+    pub players: Vec<RosterEntry>,
+    /* How long the player has for this move, if there's a limit. */
+    pub time_left: Option<Duration>,
+    /* Players should ask this for the time instead of the system. */
+    pub clock: Arc<dyn Clock>,
+    /*
+     * Set by the engine once the player is out of time and their answer no
+     * longer matters. Players that think for a while should check it and
+     * give up, or they keep running after the game has moved on.
+     */
+    pub cancelled: Arc<AtomicBool>,
+}
+
+impl TurnContext {
+    /* The first move of a game on the board, with nothing else known. */
+    pub fn new(board: Board, player_id: PlayerId) -> TurnContext {
+        TurnContext {
+            turn: board.iter_owned_edges().count(),
+            scores: board.player_ids().iter().map(|&id| (id, 0)).collect(),
+            board,
+            player_id,
+            streak: 0,
+            players: vec![],
+            time_left: None,
+            clock: Arc::new(SystemClock),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> TurnContext {
+        self.clock = clock;
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn score(&self, player_id: PlayerId) -> usize {
+        self.scores
+            .iter()
+            .find(|&&(id, _)| id == player_id)
+            .map_or(0, |&(_, count)| count)
+    }
+
+    /* Everyone else in the game, in turn order. */
+    pub fn opponents(&self) -> Vec<RosterEntry> {
+        self.players
+            .iter()
+            .filter(|(id, _)| *id != self.player_id)
+            .cloned()
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
@@ -12,6 +113,40 @@ pub trait Player {
 pub enum PlayerId {
     One = 1,
     Two = 2,
+    Three = 3,
+    Four = 4,
+    Five = 5,
+    Six = 6,
+}
+
+impl PlayerId {
+    /* Every seat at the table, in seat order. */
+    pub const ALL: [PlayerId; 6] = [
+        PlayerId::One,
+        PlayerId::Two,
+        PlayerId::Three,
+        PlayerId::Four,
+        PlayerId::Five,
+        PlayerId::Six,
+    ];
+
+    /* The id for a one-based seat number, if there is such a seat. */
+    pub fn from_seat(seat: usize) -> Option<PlayerId> {
+        if 0 < seat && seat <= Self::ALL.len() {
+            Some(Self::ALL[seat - 1])
+        } else {
+            None
+        }
+    }
+
+    pub fn seat(&self) -> usize {
+        *self as usize
+    }
+
+    /* The character used to mark boxes owned by this player. */
+    pub fn glyph(&self) -> char {
+        std::char::from_digit(self.seat() as u32, 10).unwrap()
+    }
 }
 
 impl fmt::Display for PlayerId {
@@ -22,6 +157,10 @@ impl fmt::Display for PlayerId {
             match *self {
                 PlayerId::One => "One",
                 PlayerId::Two => "Two",
+                PlayerId::Three => "Three",
+                PlayerId::Four => "Four",
+                PlayerId::Five => "Five",
+                PlayerId::Six => "Six",
             }
         )
     }
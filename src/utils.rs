@@ -5,7 +5,7 @@ pub fn pad_end(unpadded: &str, fill: &str, width: usize) -> String {
     }
     let mut right = String::new();
     let mut fill_chars: Vec<char> = fill.chars().collect();
-    if fill_chars.len() < 1 {
+    if fill_chars.is_empty() {
         fill_chars.push(' ');
     }
     for i in 0..(width - char_len) {
@@ -23,7 +23,7 @@ pub fn pad_out(unpadded: &str, fill: &str, width: usize) -> String {
     let mut left = String::new();
     let mut right = String::new();
     let mut fill_chars: Vec<char> = fill.chars().collect();
-    if fill_chars.len() < 1 {
+    if fill_chars.is_empty() {
         fill_chars.push(' ');
     }
     for i in 0..(width - char_len) {
@@ -37,6 +37,42 @@ pub fn pad_out(unpadded: &str, fill: &str, width: usize) -> String {
     format!("{}{}{}", left, unpadded, right)
 }
 
+/*
+ * One step of the SplitMix64 generator: scrambles the input into a well mixed
+ * 64 bit value. Handy for deterministic pseudo-random keys without a crate.
+ */
+pub fn splitmix64(input: u64) -> u64 {
+    let mut z = input.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/*
+ * A small seeded generator built on splitmix64, for when the same seed has to
+ * give the same numbers on every machine.
+ */
+#[derive(Clone, Debug)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> SeededRng {
+        SeededRng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        splitmix64(self.state)
+    }
+
+    /* A number from 0 up to but not including `bound`, which can't be 0. */
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
 #[test]
 fn test_pad_end() {
     assert_eq!("012", pad_end("012", " ", 3));
@@ -57,3 +93,15 @@ fn test_pad_out() {
     assert_eq!("30124", pad_out("012", "34", 5));
     assert_eq!("330124", pad_out("012", "34", 6));
 }
+
+#[test]
+fn test_seeded_rng() {
+    let mut first = SeededRng::new(7);
+    let mut second = SeededRng::new(7);
+    let mut other = SeededRng::new(8);
+    let rolls: Vec<usize> = (0..20).map(|_| first.below(6)).collect();
+
+    assert_eq!(rolls, (0..20).map(|_| second.below(6)).collect::<Vec<_>>());
+    assert_ne!(rolls, (0..20).map(|_| other.below(6)).collect::<Vec<_>>());
+    assert!(rolls.iter().all(|&roll| roll < 6));
+}
@@ -1,8 +1,10 @@
+use std::sync::atomic::Ordering;
+
 use spectral::assert_that;
 
 use doots::ai::doot;
 use doots::game::board::{edge, Board};
-use doots::players::player::{Player, PlayerId};
+use doots::players::player::{Player, PlayerId, TurnContext};
 
 #[test]
 fn test_draw_on_open_board() {
@@ -28,3 +30,31 @@ fn test_draw_to_complete_box() {
     let play = ai.play(board);
     assert_that!(play).is_equal_to(edge((0, 1), (1, 1)));
 }
+
+#[test]
+fn test_takes_box_from_drawing() {
+    let ai = doot::AI::new(PlayerId::One);
+    let board = Board::from_drawing(
+        "
+   0  1  2
+ 0 ┌──┬──╴
+   │2 │
+ 1 └──┼──╴
+      │
+ 2 ·  ╵  ·
+",
+    )
+    .expect("Parse failed");
+
+    let play = ai.play(board);
+    assert_that!(play).is_equal_to(edge((0, 2), (1, 2)));
+}
+
+#[test]
+fn test_settles_for_first_edge_when_cancelled() {
+    let ai = doot::AI::new(PlayerId::One);
+    let context = TurnContext::new(Board::new(2), PlayerId::One);
+    context.cancelled.store(true, Ordering::Relaxed);
+
+    assert_that!(ai.play_turn(context)).is_equal_to(edge((0, 0), (0, 1)));
+}
@@ -0,0 +1,95 @@
+use std::panic;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+use spectral::numeric::OrderedAssertions;
+
+use doots::ai::sleepy;
+use doots::game::board::{edge, Board};
+use doots::game::clock::{Clock, FakeClock};
+use doots::game::engine::{play_headless, Opts};
+use doots::game::error::GameError;
+use doots::players::player::{Player, PlayerId, TurnContext};
+
+/* Noon on the given day of October 2026, which started on a Thursday. */
+fn october(day: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 10, day)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap()
+}
+
+fn play_on(clock: &Arc<FakeClock>) -> doots::game::board::Edge {
+    let ai = sleepy::AI::new(PlayerId::One);
+    let clock: Arc<dyn Clock> = clock.clone();
+    ai.play_turn(TurnContext::new(Board::new(2), PlayerId::One).with_clock(clock))
+}
+
+#[test]
+fn test_naps_on_weekdays() {
+    let clock = Arc::new(FakeClock::new(october(20)));
+    let play = play_on(&clock);
+
+    assert_that!(play).is_equal_to(edge((0, 0), (0, 1)));
+    assert_that!(clock.slept()).is_equal_to(Duration::from_secs(1));
+    assert_that!(clock.now()).is_equal_to(october(20) + chrono::Duration::seconds(1));
+}
+
+#[test]
+fn test_sleeps_in_on_weekends() {
+    let clock = Arc::new(FakeClock::new(october(17)));
+    play_on(&clock);
+
+    assert_that!(clock.slept()).is_equal_to(Duration::from_secs(5));
+}
+
+#[test]
+fn test_wakes_up_when_cancelled() {
+    let clock = Arc::new(FakeClock::new(october(17)));
+    let ai = sleepy::AI::new(PlayerId::One);
+    let dyn_clock: Arc<dyn Clock> = clock.clone();
+    let context = TurnContext::new(Board::new(2), PlayerId::One).with_clock(dyn_clock);
+    context.cancelled.store(true, Ordering::Relaxed);
+
+    assert_that!(ai.play_turn(context)).is_equal_to(edge((0, 0), (0, 1)));
+    assert_that!(clock.slept()).is_equal_to(Duration::from_secs(0));
+}
+
+#[test]
+fn test_hates_mondays() {
+    let clock = Arc::new(FakeClock::new(october(19)));
+    let result = panic::catch_unwind(|| play_on(&clock));
+
+    assert_that!(result.is_err()).is_true();
+    assert_that!(clock.slept()).is_equal_to(Duration::from_secs(0));
+}
+
+#[test]
+fn test_forfeits_games_on_mondays() {
+    let clock = Arc::new(FakeClock::new(october(18)));
+    let opts = Opts {
+        rows: 2,
+        cols: 2,
+        players: vec!["boox".to_string(), "sleepy".to_string()],
+        quiet: true,
+        clock: clock.clone(),
+        ..Opts::default()
+    };
+
+    let outcome = play_headless(&opts).expect("Game failed");
+    assert_that!(outcome.forfeit).is_equal_to(None);
+    assert_that!(clock.slept().as_secs() % 5).is_equal_to(0);
+    assert_that!(clock.slept()).is_greater_than(Duration::from_secs(0));
+
+    clock.advance(Duration::from_secs(24 * 60 * 60));
+    let outcome = play_headless(&opts).expect("Game failed");
+    assert_that!(outcome.forfeit.map(|f| f.reason)).is_equal_to(Some(GameError::Panicked {
+        player_id: PlayerId::Two,
+        name: "Sleepy Two".to_string(),
+        message: "I HATE MONDAYS!".to_string(),
+    }));
+}
@@ -0,0 +1,240 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::analysis::{analyze, safe_edges, ChainEnd};
+use doots::game::board::{dotbox, edge, Board, Edge};
+use doots::players::player::PlayerId;
+
+fn draw_all(board: &mut Board, edges: Vec<Edge>) {
+    board
+        .draw_many(edges.into_iter().map(|e| (PlayerId::One, e)).collect())
+        .expect("Draw failed");
+}
+
+/* A 1x3 board with the top and bottom of every box drawn. */
+fn corridor() -> Board {
+    let mut board = Board::with_dimensions(1, 3);
+    draw_all(
+        &mut board,
+        vec![
+            edge((0, 0), (0, 1)),
+            edge((0, 1), (0, 2)),
+            edge((0, 2), (0, 3)),
+            edge((1, 0), (1, 1)),
+            edge((1, 1), (1, 2)),
+            edge((1, 2), (1, 3)),
+        ],
+    );
+    board
+}
+
+#[test]
+fn empty_board_has_only_safe_moves() {
+    let board = Board::with_dimensions(1, 3);
+    let analysis = analyze(&board);
+
+    assert_that!(analysis.chains.len()).is_equal_to(0);
+    assert_that!(analysis.loops.len()).is_equal_to(0);
+    assert_that!(analysis.capturable.len()).is_equal_to(0);
+    assert_that!(analysis.safe_moves).is_equal_to(10);
+}
+
+#[test]
+fn chain_between_ground() {
+    let analysis = analyze(&corridor());
+
+    assert_that!(analysis.chains.len()).is_equal_to(1);
+    let chain = &analysis.chains[0];
+    assert_that!(chain.length()).is_equal_to(3);
+    assert_that!(chain.boxes).is_equal_to(vec![dotbox((0, 0)), dotbox((0, 1)), dotbox((0, 2))]);
+    assert_that!(chain.ends).is_equal_to((
+        ChainEnd::Ground(edge((0, 0), (1, 0))),
+        ChainEnd::Ground(edge((0, 3), (1, 3))),
+    ));
+    assert_that!(chain.is_open()).is_false();
+    assert_that!(analysis.safe_moves).is_equal_to(0);
+}
+
+#[test]
+fn opened_chain_is_capturable() {
+    let mut board = corridor();
+    draw_all(&mut board, vec![edge((0, 0), (1, 0))]);
+    let analysis = analyze(&board);
+
+    assert_that!(analysis.capturable).is_equal_to(vec![dotbox((0, 0))]);
+    assert_that!(analysis.chains.len()).is_equal_to(1);
+    let chain = &analysis.chains[0];
+    assert_that!(chain.boxes).is_equal_to(vec![dotbox((0, 1)), dotbox((0, 2))]);
+    assert_that!(chain.ends.0).is_equal_to(ChainEnd::Capturable(dotbox((0, 0))));
+    assert_that!(chain.is_open()).is_true();
+}
+
+#[test]
+fn chains_meet_at_joint() {
+    let mut board = Board::with_dimensions(1, 3);
+    draw_all(
+        &mut board,
+        vec![
+            edge((0, 0), (0, 1)),
+            edge((1, 0), (1, 1)),
+            edge((0, 1), (0, 2)),
+            edge((0, 2), (0, 3)),
+            edge((1, 2), (1, 3)),
+        ],
+    );
+    let analysis = analyze(&board);
+
+    assert_that!(analysis.chains.len()).is_equal_to(2);
+    for chain in analysis.chains.iter() {
+        assert_that!(chain.length()).is_equal_to(1);
+        let (first, second) = chain.ends;
+        assert_that!(
+            first == ChainEnd::Joint(dotbox((0, 1))) || second == ChainEnd::Joint(dotbox((0, 1)))
+        )
+        .is_true();
+    }
+}
+
+#[test]
+fn loop_around_the_center() {
+    let mut board = Board::new(2);
+    draw_all(
+        &mut board,
+        vec![
+            edge((0, 0), (0, 1)),
+            edge((0, 1), (0, 2)),
+            edge((0, 2), (1, 2)),
+            edge((1, 2), (2, 2)),
+            edge((2, 2), (2, 1)),
+            edge((2, 1), (2, 0)),
+            edge((2, 0), (1, 0)),
+            edge((1, 0), (0, 0)),
+        ],
+    );
+    let analysis = analyze(&board);
+
+    assert_that!(analysis.chains.len()).is_equal_to(0);
+    assert_that!(analysis.loops.len()).is_equal_to(1);
+    assert_that!(analysis.loops[0].length()).is_equal_to(4);
+    assert_that!(analysis.loops[0].anchor).is_equal_to(None);
+}
+
+#[test]
+fn safe_edges_skip_boxes_with_two_drawn() {
+    let mut board = Board::with_dimensions(1, 2);
+    draw_all(&mut board, vec![edge((0, 0), (0, 1)), edge((1, 0), (1, 1))]);
+
+    assert_that!(safe_edges(&board)).is_equal_to(vec![
+        edge((0, 1), (0, 2)),
+        edge((0, 2), (1, 2)),
+        edge((1, 1), (1, 2)),
+    ]);
+}
+
+mod test_nimbers {
+    use super::*;
+    use doots::game::analysis::{component_nimbers, nimber, Nimber, NimberCache};
+
+    #[test]
+    fn single_coins() {
+        let mut board = Board::new(1);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(1));
+
+        draw_all(&mut board, vec![edge((0, 0), (0, 1))]);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(0));
+
+        draw_all(&mut board, vec![edge((0, 0), (1, 0))]);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(1));
+
+        draw_all(&mut board, vec![edge((1, 0), (1, 1))]);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(0));
+    }
+
+    #[test]
+    fn short_and_long_chains() {
+        let mut board = Board::with_dimensions(1, 2);
+        draw_all(&mut board, vec![edge((0, 0), (0, 1)), edge((0, 1), (0, 2))]);
+        draw_all(&mut board, vec![edge((1, 0), (1, 1)), edge((1, 1), (1, 2))]);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(1));
+
+        assert_that!(nimber(&corridor())).is_equal_to(Nimber::Value(0));
+    }
+
+    #[test]
+    fn offered_chain_is_loony() {
+        let mut board = corridor();
+        draw_all(&mut board, vec![edge((0, 0), (1, 0))]);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Loony);
+    }
+
+    #[test]
+    fn fully_capturable_chain_is_not_loony() {
+        let mut board = corridor();
+        draw_all(&mut board, vec![edge((0, 0), (1, 0)), edge((0, 3), (1, 3))]);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(0));
+    }
+
+    #[test]
+    fn isolated_loop() {
+        let mut board = Board::new(2);
+        draw_all(
+            &mut board,
+            vec![
+                edge((0, 0), (0, 1)),
+                edge((0, 1), (0, 2)),
+                edge((0, 2), (1, 2)),
+                edge((1, 2), (2, 2)),
+                edge((2, 2), (2, 1)),
+                edge((2, 1), (2, 0)),
+                edge((2, 0), (1, 0)),
+                edge((1, 0), (0, 0)),
+            ],
+        );
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(0));
+    }
+
+    #[test]
+    fn components_add_up() {
+        let mut board = Board::with_dimensions(1, 3);
+        draw_all(&mut board, vec![edge((0, 1), (1, 1)), edge((0, 2), (1, 2))]);
+
+        let mut components = component_nimbers(&board);
+        components.sort_by_key(|component| component.coins.clone());
+        let values: Vec<Nimber> = components.iter().map(|c| c.nimber).collect();
+        assert_that!(values).is_equal_to(vec![
+            Nimber::Value(0),
+            Nimber::Value(1),
+            Nimber::Value(0),
+        ]);
+        assert_that!(components[1].coins).is_equal_to(vec![dotbox((0, 1))]);
+        assert_that!(nimber(&board)).is_equal_to(Nimber::Value(1));
+    }
+
+    #[test]
+    fn cache_shares_symmetric_components() {
+        let mut cache = NimberCache::new();
+        let mut left = Board::with_dimensions(1, 3);
+        draw_all(&mut left, vec![edge((0, 1), (1, 1))]);
+        cache.nimber(&left);
+        let remembered = cache.len();
+
+        let mut right = Board::with_dimensions(1, 3);
+        draw_all(&mut right, vec![edge((0, 2), (1, 2))]);
+        assert_that!(cache.nimber(&right)).is_equal_to(cache.nimber(&left));
+        assert_that!(cache.len()).is_equal_to(remembered);
+    }
+
+    #[test]
+    fn cache_shares_turned_components() {
+        let mut cache = NimberCache::new();
+        let mut across = Board::with_dimensions(1, 3);
+        draw_all(&mut across, vec![edge((0, 0), (0, 1))]);
+        let value = cache.nimber(&across);
+        let remembered = cache.len();
+
+        let mut down = Board::with_dimensions(3, 1);
+        draw_all(&mut down, vec![edge((0, 0), (1, 0))]);
+        assert_that!(cache.nimber(&down)).is_equal_to(value);
+        assert_that!(cache.len()).is_equal_to(remembered);
+    }
+}
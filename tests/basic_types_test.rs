@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use spectral::assert_that;
 
 use doots::game::basic_types::{dot, edge, DotBox};
@@ -11,3 +13,14 @@ pub fn test_dot_box_edges() {
         edge((3, 7), (4, 7)),
     ])
 }
+
+#[test]
+pub fn test_edge_hash_ignores_dot_order() {
+    let mut edges = HashSet::new();
+    edges.insert(edge((3, 7), (3, 8)));
+    edges.insert(edge((3, 8), (3, 7)));
+    edges.insert(edge((3, 7), (4, 7)));
+
+    assert_that!(edges.len()).is_equal_to(2);
+    assert_that!(edges.contains(&edge((4, 7), (3, 7)))).is_equal_to(true);
+}
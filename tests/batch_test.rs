@@ -0,0 +1,153 @@
+use std::thread;
+use std::time::Duration;
+
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::batch::{game_seed, play_batch, play_in_parallel, summarize};
+use doots::game::engine::{GameOutcome, Opts};
+use doots::game::error::GameError;
+use doots::game::tournament::{run_tournament, TournamentOpts};
+use doots::players::choose::{factories, factory, seat};
+use doots::players::player::{PlayerId, SeatedPlayer};
+
+fn opts() -> Opts {
+    Opts {
+        rows: 3,
+        cols: 3,
+        players: vec!["doot".to_string(), "boox".to_string()],
+        quiet: true,
+        random_opening: 4,
+        seed: 21,
+        ..Opts::default()
+    }
+}
+
+fn moves(outcomes: &[GameOutcome]) -> Vec<String> {
+    outcomes
+        .iter()
+        .map(|outcome| format!("{}", outcome.board))
+        .collect()
+}
+
+/* Games and their options get handed to other threads. */
+fn is_send<T: Send>() {}
+
+#[test]
+fn players_and_options_are_send() {
+    is_send::<SeatedPlayer>();
+    is_send::<Opts>();
+}
+
+mod test_factories {
+    use super::*;
+
+    #[test]
+    fn make_fresh_players_for_each_seat() {
+        let doot = factory("doot");
+
+        assert_that!(doot(PlayerId::One).name()).is_equal_to("Doot One".to_string());
+        assert_that!(doot(PlayerId::Three).name()).is_equal_to("Doot Three".to_string());
+        assert_that!(factory("Sam")(PlayerId::Two).name()).is_equal_to("Sam".to_string());
+    }
+
+    #[test]
+    fn seat_players_in_order() {
+        let names = vec!["boox".to_string(), "sleepy".to_string()];
+        let seated = seat(&factories(&names).unwrap());
+        let seats: Vec<(PlayerId, String)> = seated
+            .iter()
+            .map(|(id, player)| (*id, player.name()))
+            .collect();
+
+        assert_that!(seats).is_equal_to(vec![
+            (PlayerId::One, "Boox One".to_string()),
+            (PlayerId::Two, "Sleepy Two".to_string()),
+        ]);
+        assert_that!(factories(&names[..1]).is_err()).is_true();
+    }
+}
+
+mod test_parallel {
+    use super::*;
+
+    #[test]
+    fn keeps_results_in_order() {
+        let mut reported = vec![];
+        let results = play_in_parallel(
+            20,
+            4,
+            |index| {
+                // Later indexes finish first.
+                thread::sleep(Duration::from_millis(20 - index as u64));
+                index * 10
+            },
+            |index, &result| reported.push((index, result)),
+        );
+
+        assert_that!(results.clone()).is_equal_to((0..20).map(|i| i * 10).collect::<Vec<_>>());
+        assert_that!(reported).is_equal_to(results.into_iter().enumerate().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn handles_no_work() {
+        let results: Vec<usize> = play_in_parallel(0, 8, |index| index, |_, _| {});
+
+        assert_that!(results.is_empty()).is_true();
+    }
+
+    #[test]
+    fn plays_the_same_games_on_any_number_of_threads() {
+        let alone = play_batch(&opts(), 12, 1).expect("Batch failed");
+        let together = play_batch(&opts(), 12, 4).expect("Batch failed");
+
+        assert_that!(moves(&alone)).is_equal_to(moves(&together));
+        assert_that!(alone.iter().all(|outcome| outcome.board.is_full())).is_true();
+        assert_that!(game_seed(21, 0) == game_seed(21, 1)).is_false();
+    }
+
+    #[test]
+    fn plays_the_same_tournament_on_any_number_of_threads() {
+        let tournament = |jobs| {
+            run_tournament(&TournamentOpts {
+                players: vec!["doot".to_string(), "boox".to_string()],
+                sizes: vec![2, 3],
+                games: 3,
+                random_opening: 3,
+                seed: 8,
+                ratings: None,
+                jobs,
+            })
+            .expect("Tournament failed")
+        };
+
+        assert_that!(tournament(1)).is_equal_to(tournament(3));
+    }
+}
+
+#[test]
+fn only_plays_ai_batches() {
+    let error = play_batch(
+        &Opts {
+            players: vec!["doot".to_string(), "hoomin".to_string()],
+            ..opts()
+        },
+        2,
+        2,
+    )
+    .err();
+
+    assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+}
+
+#[test]
+fn sums_up_a_batch() {
+    let outcomes = play_batch(&opts(), 4, 2).expect("Batch failed");
+    let summary = summarize(&opts().players, &outcomes);
+    let lines: Vec<&str> = summary.lines().collect();
+
+    assert_that!(lines.len()).is_equal_to(3);
+    assert_that!(lines[0].to_string())
+        .is_equal_to("Player      Wins  Ties  Forfeits  Average boxes".to_string());
+    assert_that!(lines[1].starts_with("1 doot")).is_true();
+}
@@ -1,7 +1,7 @@
 use spectral::boolean::BooleanAssertions;
 use spectral::{assert_that, asserting};
 
-use doots::game::board::{dot, dotbox, edge, Board, Dot, DotBox, Edge, WinnerResult};
+use doots::game::board::{dot, dotbox, edge, Board, Dot, DotBox, Edge, OwnedEdge, WinnerResult};
 use doots::players::player::PlayerId;
 
 fn safely_draw_boxes(board: &mut Board, first_owner_id: PlayerId, boxes: &Vec<DotBox>) {
@@ -175,7 +175,7 @@ mod test_winner {
         let p1_boxes = vec![DotBox(dot(5, 5)), DotBox(dot(6, 6))];
         safely_draw_boxes(&mut board, PlayerId::Two, &p1_boxes);
 
-        println!("{}", board.to_string());
+        println!("{}", board.drawing());
 
         let owner_to_boxes = board.owner_to_boxes();
         assert_that!(*owner_to_boxes.get(&PlayerId::One).unwrap()).is_equal_to(p1_boxes);
@@ -643,13 +643,13 @@ mod test_to_string {
         ]
         .join("\n");
 
-        let result = board.to_string();
+        let result = board.drawing();
 
         assert_eq!(
             expected,
             result,
             "\n{}",
-            vec!["Expected:", &expected, "Received:", &result].join("\n")
+            ["Expected:", &expected, "Received:", &result].join("\n")
         );
     }
 
@@ -665,7 +665,7 @@ mod test_to_string {
             ])
             .expect("Draw failed");
 
-        let expected = vec![
+        let expected = [
             "   0  1  2  ",
             " 0 ·  ·  ·  ",
             "            ",
@@ -675,13 +675,455 @@ mod test_to_string {
         ]
         .join("\n");
 
-        let result = board.to_string();
+        let result = board.drawing();
 
         assert_eq!(
             expected,
             result,
             "\n{}",
-            vec!["Expected:", &expected, "Received:", &result].join("\n")
+            ["Expected:", &expected, "Received:", &result].join("\n")
         );
     }
 }
+
+mod test_indexes {
+    use super::*;
+
+    #[test]
+    fn edge_indexes_are_unique_and_dense() {
+        let board = Board::new(3);
+        let mut indexes: Vec<usize> = board
+            .iter_edges()
+            .map(|e| board.edge_index(e).unwrap())
+            .collect();
+        indexes.sort();
+        let expected: Vec<usize> = (0..(board.edge_count() as usize)).collect();
+        assert_that!(indexes).is_equal_to(expected);
+    }
+
+    #[test]
+    fn edge_index_ignores_dot_order() {
+        let board = Board::new(3);
+        assert_that!(board.edge_index(edge((1, 2), (1, 1))))
+            .is_equal_to(board.edge_index(edge((1, 1), (1, 2))));
+        assert_that!(board.edge_index(edge((4, 4), (4, 5)))).is_equal_to(None);
+    }
+
+    #[test]
+    fn box_edge_count_tracks_draws() {
+        let mut board = Board::new(2);
+        board
+            .draw_many(vec![
+                (PlayerId::One, edge((1, 1), (1, 2))),
+                (PlayerId::Two, edge((1, 1), (2, 1))),
+            ])
+            .expect("Draw failed");
+
+        assert_that!(board.box_edge_count(dotbox((1, 1)))).is_equal_to(2);
+        assert_that!(board.box_edge_count(dotbox((0, 1)))).is_equal_to(1);
+        assert_that!(board.box_edge_count(dotbox((0, 0)))).is_equal_to(0);
+    }
+
+    #[test]
+    fn draw_many_rejects_duplicates_within_the_batch() {
+        let mut board = Board::new(2);
+        let result = board.draw_many(vec![
+            (PlayerId::One, edge((0, 0), (0, 1))),
+            (PlayerId::Two, edge((0, 1), (0, 0))),
+        ]);
+
+        assert_that!(result.is_err()).is_true();
+        assert_that!(board.iter_owned_edges().count()).is_equal_to(0);
+        assert_that!(board.is_free(edge((0, 0), (0, 1)))).is_true();
+    }
+
+    #[test]
+    fn iter_owned_edges_keeps_draw_order() {
+        let mut board = Board::new(2);
+        let drawn = vec![
+            (PlayerId::Two, edge((2, 1), (2, 2))),
+            (PlayerId::One, edge((0, 0), (0, 1))),
+            (PlayerId::Two, edge((1, 1), (1, 2))),
+        ];
+        board.draw_many(drawn.clone()).expect("Draw failed");
+
+        assert_that!(board.iter_owned_edges().collect::<Vec<_>>()).is_equal_to(drawn);
+    }
+}
+
+mod test_rectangular {
+    use super::*;
+
+    #[test]
+    fn counts() {
+        let board = Board::with_dimensions(3, 5);
+        assert_that!(board.dot_count()).is_equal_to(24);
+        assert_that!(board.edge_count()).is_equal_to(38);
+        assert_that!(board.box_count()).is_equal_to(15);
+        assert_that!(board.iter_dots().count()).is_equal_to(24);
+        assert_that!(board.iter_edges().count()).is_equal_to(38);
+        assert_that!(board.iter_boxes().count()).is_equal_to(15);
+    }
+
+    #[test]
+    fn dot_fits() {
+        let board = Board::with_dimensions(3, 5);
+        assert_that!(board.dot_fits(dot(3, 5))).is_true();
+        assert_that!(board.dot_fits(dot(4, 5))).is_false();
+        assert_that!(board.dot_fits(dot(3, 6))).is_false();
+    }
+
+    #[test]
+    fn associated_boxes() {
+        let board = Board::with_dimensions(3, 5);
+        assert_that!(board.associated_boxes(edge((1, 5), (2, 5))))
+            .is_equal_to(vec![dotbox((1, 4))]);
+        assert_that!(board.associated_boxes(edge((3, 4), (3, 5))))
+            .is_equal_to(vec![dotbox((2, 4))]);
+        assert_that!(board.associated_boxes(edge((4, 0), (4, 1)))).is_equal_to(vec![]);
+    }
+
+    #[test]
+    fn drawing() {
+        let mut board = Board::with_dimensions(1, 2);
+        safely_draw_boxes(&mut board, PlayerId::One, &vec![dotbox((0, 1))]);
+
+        let expected = [
+            "   0  1  2  ",
+            " 0 ·  ┌──┐  ",
+            "      │2 │  ",
+            " 1 ·  └──┘  ",
+        ]
+        .join("\n");
+
+        assert_eq!(expected, board.drawing());
+    }
+}
+
+mod test_more_players {
+    use super::*;
+
+    #[test]
+    fn player_ids_from_seats() {
+        assert_that!(PlayerId::from_seat(3)).is_equal_to(Some(PlayerId::Three));
+        assert_that!(PlayerId::from_seat(6).map(|id| id.glyph())).is_equal_to(Some('6'));
+        assert_that!(PlayerId::from_seat(0)).is_equal_to(None);
+        assert_that!(PlayerId::from_seat(7)).is_equal_to(None);
+    }
+
+    #[test]
+    fn owner_to_boxes_includes_every_player() {
+        let mut board =
+            Board::new(2).with_player_ids(vec![PlayerId::One, PlayerId::Two, PlayerId::Three]);
+        board
+            .draw_many(vec![
+                (PlayerId::One, edge((0, 0), (0, 1))),
+                (PlayerId::Two, edge((0, 0), (1, 0))),
+                (PlayerId::Three, edge((1, 0), (1, 1))),
+                (PlayerId::Three, edge((0, 1), (1, 1))),
+            ])
+            .expect("Draw failed");
+
+        let owner_to_boxes = board.owner_to_boxes();
+        assert_that!(owner_to_boxes.len()).is_equal_to(3);
+        assert_that!(*owner_to_boxes.get(&PlayerId::Three).unwrap())
+            .is_equal_to(vec![dotbox((0, 0))]);
+        assert_eq!('3', board.choose_owner_char(dot(0, 0)));
+    }
+
+    #[test]
+    fn three_way_tie() {
+        let mut board = Board::with_dimensions(1, 3).with_player_ids(vec![
+            PlayerId::One,
+            PlayerId::Two,
+            PlayerId::Three,
+        ]);
+        safely_draw_boxes(&mut board, PlayerId::One, &vec![dotbox((0, 0))]);
+        safely_draw_boxes(&mut board, PlayerId::One, &vec![dotbox((0, 1))]);
+        board
+            .draw_many(vec![
+                (PlayerId::Three, edge((0, 2), (0, 3))),
+                (PlayerId::Three, edge((1, 2), (1, 3))),
+                (PlayerId::Three, edge((0, 3), (1, 3))),
+            ])
+            .expect("Draw failed");
+
+        assert_that!(board.winner()).is_equal_to(WinnerResult::Tie(
+            vec![PlayerId::One, PlayerId::Two, PlayerId::Three],
+            1,
+        ));
+    }
+}
+
+mod test_draw_errors {
+    use super::*;
+    use doots::game::error::DrawError;
+
+    #[test]
+    fn tells_errors_apart() {
+        let mut board = Board::with_dimensions(1, 2);
+        board
+            .draw((PlayerId::One, edge((0, 0), (0, 1))))
+            .expect("Draw failed");
+
+        assert_that!(board.draw((PlayerId::Two, edge((0, 0), (1, 1)))))
+            .is_equal_to(Err(DrawError::InvalidEdge(edge((0, 0), (1, 1)))));
+        assert_that!(board.draw((PlayerId::Two, edge((1, 2), (1, 3))))).is_equal_to(Err(
+            DrawError::DoesNotFit {
+                edge: edge((1, 2), (1, 3)),
+                rows: 1,
+                cols: 2,
+            },
+        ));
+        assert_that!(board.draw((PlayerId::Two, edge((0, 1), (0, 0)))))
+            .is_equal_to(Err(DrawError::AlreadyDrawn(edge((0, 1), (0, 0)))));
+    }
+
+    #[test]
+    fn batch_duplicates_are_already_drawn() {
+        let mut board = Board::new(1);
+        let result = board.draw_many(vec![
+            (PlayerId::One, edge((0, 0), (0, 1))),
+            (PlayerId::Two, edge((0, 1), (0, 0))),
+        ]);
+
+        assert_that!(result).is_equal_to(Err(DrawError::AlreadyDrawn(edge((0, 1), (0, 0)))));
+        assert_that!(board.iter_owned_edges().count()).is_equal_to(0);
+    }
+
+    #[test]
+    fn keeps_the_old_messages() {
+        let error = DrawError::AlreadyDrawn(edge((0, 0), (0, 1)));
+        assert_that!(error.to_string())
+            .is_equal_to(format!("Cannot redraw edge: {}", edge((0, 0), (0, 1))));
+        assert_that!(DrawError::DoesNotFit {
+            edge: edge((0, 0), (0, 1)),
+            rows: 2,
+            cols: 3
+        }
+        .to_string()
+        .ends_with("does not fit in board of size 2x3"))
+        .is_true();
+    }
+}
+
+mod test_history {
+    use super::*;
+
+    fn claimed_board() -> Board {
+        let mut board = Board::new(2);
+        board
+            .draw_many(vec![
+                (PlayerId::One, edge((1, 1), (1, 2))),
+                (PlayerId::Two, edge((1, 1), (2, 1))),
+                (PlayerId::One, edge((2, 2), (1, 2))),
+                (PlayerId::Two, edge((2, 2), (2, 1))),
+            ])
+            .expect("Draw failed");
+        board
+    }
+
+    #[test]
+    fn undo_releases_edge_and_box() {
+        let mut board = claimed_board();
+
+        assert_that!(board.undo()).is_equal_to(Some((PlayerId::Two, edge((2, 2), (2, 1)))));
+        assert_that!(board.is_free(edge((2, 1), (2, 2)))).is_true();
+        assert_that!(board.box_owner(dot(1, 1))).is_equal_to(None);
+        assert_that!(board.box_edge_count(dotbox((1, 1)))).is_equal_to(3);
+        assert_that!(board.would_claim_box(edge((2, 1), (2, 2)))).is_true();
+    }
+
+    #[test]
+    fn undo_on_empty_board() {
+        let mut board = Board::new(2);
+        assert_that!(board.undo()).is_equal_to(None);
+        assert_that!(board.redo()).is_equal_to(None);
+    }
+
+    #[test]
+    fn redo_restores_undone_edges_in_order() {
+        let mut board = claimed_board();
+        let drawn: Vec<OwnedEdge> = board.iter_owned_edges().collect();
+        board.undo();
+        board.undo();
+
+        assert_that!(board.redo()).is_equal_to(Some(drawn[2]));
+        assert_that!(board.redo()).is_equal_to(Some(drawn[3]));
+        assert_that!(board.redo()).is_equal_to(None);
+        assert_that!(board.iter_owned_edges().collect::<Vec<_>>()).is_equal_to(drawn);
+        assert_that!(board.box_owner(dot(1, 1))).is_equal_to(Some(PlayerId::Two));
+    }
+
+    #[test]
+    fn drawing_clears_redo() {
+        let mut board = claimed_board();
+        board.undo();
+        board
+            .draw((PlayerId::One, edge((0, 0), (0, 1))))
+            .expect("Draw failed");
+
+        assert_that!(board.redo()).is_equal_to(None);
+    }
+
+    #[test]
+    fn truncate_to_turn() {
+        let mut board = claimed_board();
+        board.truncate_to(1);
+
+        assert_that!(board.iter_owned_edges().collect::<Vec<_>>())
+            .is_equal_to(vec![(PlayerId::One, edge((1, 1), (1, 2)))]);
+        assert_that!(board.box_edge_count(dotbox((1, 1)))).is_equal_to(1);
+
+        board.truncate_to(3);
+        assert_that!(board.iter_owned_edges().count()).is_equal_to(1);
+        assert_that!(board.redo()).is_equal_to(Some((PlayerId::Two, edge((1, 1), (2, 1)))));
+    }
+}
+
+mod test_position_identity {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn draw_order_does_not_matter() {
+        let mut board_a = Board::new(2);
+        board_a
+            .draw_many(vec![
+                (PlayerId::One, edge((0, 0), (0, 1))),
+                (PlayerId::Two, edge((1, 1), (2, 1))),
+                (PlayerId::One, edge((0, 2), (1, 2))),
+            ])
+            .expect("Draw failed");
+        let mut board_b = Board::new(2);
+        board_b
+            .draw_many(vec![
+                (PlayerId::Two, edge((1, 2), (0, 2))),
+                (PlayerId::Two, edge((0, 0), (0, 1))),
+                (PlayerId::One, edge((2, 1), (1, 1))),
+            ])
+            .expect("Draw failed");
+
+        assert_that!(board_a.zobrist()).is_equal_to(board_b.zobrist());
+        assert_that!(board_a.position_key()).is_equal_to(board_b.position_key());
+        assert_that!(board_a == board_b).is_true();
+
+        let mut positions = HashSet::new();
+        positions.insert(board_a);
+        positions.insert(board_b);
+        assert_that!(positions.len()).is_equal_to(1);
+    }
+
+    #[test]
+    fn box_owners_matter() {
+        let mut board_a = Board::new(1);
+        safely_draw_boxes(&mut board_a, PlayerId::One, &vec![dotbox((0, 0))]);
+        let mut board_b = Board::new(1);
+        safely_draw_boxes(&mut board_b, PlayerId::Two, &vec![dotbox((0, 0))]);
+
+        assert_that!(board_a.zobrist()).is_not_equal_to(board_b.zobrist());
+        assert_that!(board_a == board_b).is_false();
+    }
+
+    #[test]
+    fn dimensions_matter() {
+        assert_that!(Board::with_dimensions(2, 3).zobrist())
+            .is_not_equal_to(Board::with_dimensions(3, 2).zobrist());
+        assert_that!(Board::with_dimensions(2, 3) == Board::with_dimensions(3, 2)).is_false();
+    }
+
+    #[test]
+    fn undo_restores_hash() {
+        let mut board = Board::new(2);
+        let empty_hash = board.zobrist();
+        safely_draw_boxes(&mut board, PlayerId::One, &vec![dotbox((1, 1))]);
+        let claimed_hash = board.zobrist();
+
+        board.undo();
+        assert_that!(board.zobrist()).is_not_equal_to(claimed_hash);
+        board.redo();
+        assert_that!(board.zobrist()).is_equal_to(claimed_hash);
+        board.truncate_to(0);
+        assert_that!(board.zobrist()).is_equal_to(empty_hash);
+        assert_that!(board == Board::new(2)).is_true();
+    }
+}
+
+mod test_from_drawing {
+    use super::*;
+
+    #[test]
+    fn round_trips_the_drawing() {
+        let mut board = Board::with_dimensions(2, 3);
+        board
+            .draw_many(vec![
+                (PlayerId::One, edge((1, 1), (1, 2))),
+                (PlayerId::Two, edge((1, 1), (2, 1))),
+                (PlayerId::One, edge((1, 2), (2, 2))),
+                (PlayerId::Two, edge((2, 1), (2, 2))),
+                (PlayerId::Two, edge((0, 0), (0, 1))),
+                (PlayerId::One, edge((0, 3), (1, 3))),
+            ])
+            .expect("Draw failed");
+
+        let rebuilt = Board::from_drawing(&board.drawing()).expect("Parse failed");
+        assert_that!(rebuilt == board).is_true();
+        assert_that!(rebuilt.drawing()).is_equal_to(board.drawing());
+    }
+
+    #[test]
+    fn reads_trimmed_transcript() {
+        // From the README, with the trailing whitespace a terminal drops:
+        let drawing = "
+   0  1  2  3  4
+ 0 ┌──┐  ·  ·  ·
+   │2 │
+ 1 └──┤  ·  ·  ·
+      │
+ 2 ·  ╵  ·  ·  ·
+
+ 3 ·  ·  ·  ·  ·
+
+ 4 ·  ·  ·  ·  ·
+";
+        let board = Board::from_drawing(drawing).expect("Parse failed");
+
+        assert_that!((board.rows(), board.cols())).is_equal_to((4, 4));
+        assert_that!(board.iter_owned_edges().count()).is_equal_to(5);
+        assert_that!(board.box_owner(dot(0, 0))).is_equal_to(Some(PlayerId::Two));
+        assert_that!(board.is_drawn(edge((1, 1), (2, 1)))).is_true();
+    }
+
+    #[test]
+    fn finds_an_order_for_neighboring_owners() {
+        let drawing = [
+            "   0  1  2  ",
+            " 0 ┌──┬──┐  ",
+            "   │1 │2 │  ",
+            " 1 └──┴──┘  ",
+        ]
+        .join("\n");
+        let board = Board::from_drawing(&drawing).expect("Parse failed");
+
+        assert_that!(board.box_owner(dot(0, 0))).is_equal_to(Some(PlayerId::One));
+        assert_that!(board.box_owner(dot(0, 1))).is_equal_to(Some(PlayerId::Two));
+    }
+
+    #[test]
+    fn rejects_inconsistent_drawings() {
+        let unowned = ["   0  1 ", " 0 ┌──┐", "   │  │", " 1 └──┘"].join("\n");
+        assert_that!(Board::from_drawing(&unowned).is_err()).is_true();
+
+        let owned_open = ["   0  1 ", " 0 ┌──╴", "   │1", " 1 └──╴"].join("\n");
+        assert_that!(Board::from_drawing(&owned_open).is_err()).is_true();
+
+        let mismatched = ["   0  1 ", " 0 ┌──┐", "   │1 │", " 1 └──╴"].join("\n");
+        assert_that!(Board::from_drawing(&mismatched).is_err()).is_true();
+
+        let garbage = ["   0  1 ", " 0 x  ·", "", " 1 ·  ·"].join("\n");
+        assert_that!(Board::from_drawing(&garbage).is_err()).is_true();
+
+        assert_that!(Board::from_drawing("").is_err()).is_true();
+    }
+}
@@ -0,0 +1,74 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::board::{dotbox, edge, Board};
+use doots::game::coins::Node;
+use doots::players::player::PlayerId;
+
+#[test]
+fn empty_board_graph() {
+    let graph = Board::new(2).to_coins_graph();
+
+    assert_that!(graph.coins().len()).is_equal_to(4);
+    assert_that!(graph.strings().len()).is_equal_to(12);
+    assert_that!(graph.degree(Node::Coin(dotbox((0, 0))))).is_equal_to(4);
+    assert_that!(graph.degree(Node::Ground)).is_equal_to(8);
+}
+
+#[test]
+fn neighbors_include_ground_per_string() {
+    let graph = Board::new(2).to_coins_graph();
+    let mut neighbors = graph.neighbors(Node::Coin(dotbox((0, 0))));
+    neighbors.sort();
+
+    assert_that!(neighbors).is_equal_to(vec![
+        Node::Ground,
+        Node::Ground,
+        Node::Coin(dotbox((0, 1))),
+        Node::Coin(dotbox((1, 0))),
+    ]);
+}
+
+#[test]
+fn drawn_edges_cut_strings_and_claimed_boxes_drop_out() {
+    let mut board = Board::new(2);
+    board
+        .draw_many(vec![
+            (PlayerId::One, edge((0, 0), (0, 1))),
+            (PlayerId::Two, edge((0, 0), (1, 0))),
+            (PlayerId::One, edge((1, 0), (1, 1))),
+            (PlayerId::Two, edge((0, 1), (1, 1))),
+        ])
+        .expect("Draw failed");
+    let graph = board.to_coins_graph();
+
+    assert_that!(graph.coins().len()).is_equal_to(3);
+    assert_that!(graph.strings().len()).is_equal_to(8);
+    assert_that!(graph.string_for_edge(edge((0, 1), (1, 1)))).is_equal_to(None);
+    assert_that!(graph.degree(Node::Coin(dotbox((0, 1))))).is_equal_to(3);
+}
+
+#[test]
+fn strings_lead_back_to_edges() {
+    let graph = Board::with_dimensions(1, 2).to_coins_graph();
+
+    let string = graph.string_for_edge(edge((1, 1), (0, 1))).unwrap();
+    assert_that!(string.ends).is_equal_to((Node::Coin(dotbox((0, 0))), Node::Coin(dotbox((0, 1)))));
+    assert_that!(graph.edges_between(Node::Coin(dotbox((0, 1))), Node::Coin(dotbox((0, 0)))))
+        .is_equal_to(vec![edge((0, 1), (1, 1))]);
+    assert_that!(graph
+        .edges_between(Node::Ground, Node::Coin(dotbox((0, 1))))
+        .len())
+    .is_equal_to(3);
+}
+
+#[test]
+fn graphviz_export() {
+    let graph = Board::with_dimensions(1, 2).to_coins_graph();
+    let source = graph.to_graphviz();
+
+    assert_that!(source.starts_with("graph coins {")).is_true();
+    assert_that!(source.contains("coin_0_1 [label=\"(0, 1)\", pos=\"72,0!\"];")).is_true();
+    assert_that!(source.contains("coin_0_0 -- coin_0_1")).is_true();
+    assert_that!(source.matches(" -- ground").count()).is_equal_to(6);
+}
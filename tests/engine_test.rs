@@ -0,0 +1,486 @@
+use std::error::Error;
+
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::board::{edge, Board, WinnerResult};
+use doots::game::engine::{play_headless, Opts};
+use doots::game::error::{DrawError, GameError};
+use doots::players::player::PlayerId;
+
+fn opts(players: &[&str]) -> Opts {
+    Opts {
+        rows: 3,
+        cols: 3,
+        players: players.iter().map(|p| p.to_string()).collect(),
+        quiet: true,
+        ..Opts::default()
+    }
+}
+
+mod test_headless {
+    use super::*;
+
+    #[test]
+    fn returns_the_outcome() {
+        let outcome = play_headless(&opts(&["doot", "boox"])).expect("Game failed");
+
+        assert_that!(outcome.board.is_full()).is_true();
+        assert_that!(outcome.forfeit.clone()).is_equal_to(None);
+        assert_that!(outcome.result.clone()).is_equal_to(outcome.board.winner());
+        assert_that!(outcome.boxes_for(PlayerId::One) + outcome.boxes_for(PlayerId::Two))
+            .is_equal_to(9);
+        assert_that!(outcome.boxes_for(PlayerId::One))
+            .is_equal_to(outcome.board.owned_boxes_count(PlayerId::One));
+    }
+
+    #[test]
+    fn counts_turns_and_streaks() {
+        let outcome = play_headless(&opts(&["boox", "boox"])).expect("Game failed");
+
+        let streak_moves: usize = outcome.streaks.iter().map(|s| s.length).sum();
+        let streak_boxes: usize = outcome.streaks.iter().map(|s| s.boxes).sum();
+        assert_that!(streak_boxes).is_equal_to(9);
+        // Every box finishing move earns another, except the last move of the game.
+        assert_that!(outcome.turn_count + streak_moves - 1).is_equal_to(24);
+        for streak in outcome.streaks.iter() {
+            assert_that!(
+                outcome
+                    .board
+                    .iter_owned_edges()
+                    .nth(streak.start)
+                    .unwrap()
+                    .0
+            )
+            .is_equal_to(streak.player_id);
+        }
+    }
+
+    #[test]
+    fn is_repeatable() {
+        let first = play_headless(&opts(&["doot", "boox", "boox"])).expect("Game failed");
+        let second = play_headless(&opts(&["doot", "boox", "boox"])).expect("Game failed");
+
+        assert_that!(first.box_counts.clone()).is_equal_to(second.box_counts.clone());
+        assert_that!(first.board.iter_owned_edges().collect::<Vec<_>>())
+            .is_equal_to(second.board.iter_owned_edges().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn reports_setup_errors() {
+        let error = play_headless(&opts(&["doot"])).err();
+        assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+
+        let mut bad_order = opts(&["doot", "boox"]);
+        bad_order.turn_order = vec![PlayerId::One, PlayerId::One];
+        let error = play_headless(&bad_order).err();
+        assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+    }
+}
+
+mod test_positions {
+    use super::*;
+
+    fn position() -> Board {
+        "2x2 1:0,0h 2:0,0v 1:1,0h 2:2,0h".parse::<Board>().unwrap()
+    }
+
+    #[test]
+    fn plays_the_remaining_edges() {
+        let outcome = play_headless(&Opts {
+            rows: 5,
+            position: Some(position()),
+            to_move: Some(PlayerId::Two),
+            ..opts(&["boox", "doot"])
+        })
+        .expect("Game failed");
+
+        let drawn: Vec<_> = outcome.board.iter_owned_edges().collect();
+        let setup: Vec<_> = position().iter_owned_edges().collect();
+        assert_that!(outcome.board.rows()).is_equal_to(2);
+        assert_that!(outcome.board.is_full()).is_true();
+        assert_that!(drawn[..4].to_vec()).is_equal_to(setup);
+        assert_that!(drawn[4].0).is_equal_to(PlayerId::Two);
+        assert_that!(outcome.boxes_for(PlayerId::One) + outcome.boxes_for(PlayerId::Two))
+            .is_equal_to(4);
+    }
+
+    #[test]
+    fn ends_right_away_on_a_full_board() {
+        let full = play_headless(&opts(&["boox", "doot"])).expect("Game failed");
+        let outcome = play_headless(&Opts {
+            position: Some(full.board.clone()),
+            ..opts(&["doot", "doot"])
+        })
+        .expect("Game failed");
+
+        assert_that!(outcome.turn_count).is_equal_to(0);
+        assert_that!(outcome.result).is_equal_to(full.result);
+    }
+
+    #[test]
+    fn reports_setup_errors() {
+        let error = play_headless(&Opts {
+            to_move: Some(PlayerId::Three),
+            ..opts(&["doot", "boox"])
+        })
+        .err();
+        assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+
+        let error = play_headless(&Opts {
+            position: Some("2x2 p1,2,3 3:0,0h".parse::<Board>().unwrap()),
+            ..opts(&["doot", "boox"])
+        })
+        .err();
+        assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+
+        for position in [Board::with_dimensions(0, 0), Board::with_dimensions(1, 101)].iter() {
+            let error = play_headless(&Opts {
+                position: Some(position.clone()),
+                ..opts(&["doot", "boox"])
+            })
+            .err();
+            assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+        }
+        let error = play_headless(&Opts {
+            rows: 0,
+            ..opts(&["doot", "boox"])
+        })
+        .err();
+        assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+    }
+}
+
+mod test_time_limits {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    use doots::game::board::{Board, Edge};
+    use doots::game::engine::{play_seated, TimeoutPolicy};
+    use doots::players::player::{Player, SeatedPlayer, TurnContext};
+
+    /* Takes the first free edge, after thinking for a while. */
+    struct Slowpoke {
+        delay: Duration,
+        time_left_seen: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    impl Player for Slowpoke {
+        fn name(&self) -> String {
+            "Slowpoke".to_string()
+        }
+
+        fn play(&self, board: Board) -> Edge {
+            thread::sleep(self.delay);
+            board.iter_edges().find(|&e| board.is_free(e)).unwrap()
+        }
+
+        fn play_turn(&self, context: TurnContext) -> Edge {
+            if let Some(time_left) = context.time_left {
+                self.time_left_seen.lock().unwrap().push(time_left);
+            }
+            self.play(context.board)
+        }
+    }
+
+    fn seated(delay_ms: u64) -> (Vec<SeatedPlayer>, Arc<Mutex<Vec<Duration>>>) {
+        let time_left_seen = Arc::new(Mutex::new(vec![]));
+        let slowpoke = Slowpoke {
+            delay: Duration::from_millis(delay_ms),
+            time_left_seen: Arc::clone(&time_left_seen),
+        };
+        let players: Vec<SeatedPlayer> = vec![
+            (PlayerId::One, Box::new(slowpoke)),
+            (
+                PlayerId::Two,
+                Box::new(doots::ai::boox::AI::new(PlayerId::Two)),
+            ),
+        ];
+        (players, time_left_seen)
+    }
+
+    fn timed_opts(move_ms: Option<u64>, game_ms: Option<u64>, on_timeout: TimeoutPolicy) -> Opts {
+        Opts {
+            rows: 2,
+            cols: 2,
+            move_time: move_ms.map(Duration::from_millis),
+            game_time: game_ms.map(Duration::from_millis),
+            on_timeout,
+            ..Opts::default()
+        }
+    }
+
+    #[test]
+    fn forfeits_a_slow_move() {
+        let (players, _) = seated(2000);
+        let opts = timed_opts(Some(50), None, TimeoutPolicy::Forfeit);
+        let outcome = play_seated(&opts, players, &mut ()).expect("Game failed");
+
+        let forfeit = outcome.forfeit.expect("No forfeit");
+        assert_that!(forfeit.player_id).is_equal_to(PlayerId::One);
+        assert_that!(forfeit.reason).is_equal_to(GameError::TimedOut {
+            player_id: PlayerId::One,
+            name: "Slowpoke".to_string(),
+            limit: Duration::from_millis(50),
+        });
+        assert_that!(outcome.result).is_equal_to(WinnerResult::Winner(PlayerId::Two, 0));
+        assert_that!(outcome.board.iter_owned_edges().count()).is_equal_to(0);
+    }
+
+    #[test]
+    fn falls_back_to_a_safe_edge() {
+        let (players, _) = seated(2000);
+        let opts = timed_opts(Some(20), None, TimeoutPolicy::Fallback);
+        let outcome = play_seated(&opts, players, &mut ()).expect("Game failed");
+
+        assert_that!(outcome.forfeit).is_equal_to(None);
+        assert_that!(outcome.board.is_full()).is_true();
+        let (_, first_edge) = outcome.board.iter_owned_edges().next().unwrap();
+        assert_that!(first_edge).is_equal_to(edge((0, 0), (0, 1)));
+    }
+
+    #[test]
+    fn forfeits_when_the_game_clock_runs_out() {
+        let (players, _) = seated(40);
+        let opts = timed_opts(None, Some(100), TimeoutPolicy::Forfeit);
+        let outcome = play_seated(&opts, players, &mut ()).expect("Game failed");
+
+        assert_that!(outcome.forfeit.map(|f| f.player_id)).is_equal_to(Some(PlayerId::One));
+        let (_, slowpoke_time) = outcome.time_used[0];
+        assert_that!(slowpoke_time >= Duration::from_millis(100)).is_true();
+    }
+
+    #[test]
+    fn reports_time_left_to_the_player() {
+        let (players, time_left_seen) = seated(5);
+        let opts = timed_opts(Some(1000), Some(2000), TimeoutPolicy::Forfeit);
+        let outcome = play_seated(&opts, players, &mut ()).expect("Game failed");
+
+        assert_that!(outcome.forfeit).is_equal_to(None);
+        let seen = time_left_seen.lock().unwrap().clone();
+        assert_that!(seen.len() > 1).is_true();
+        assert_that!(seen[0]).is_equal_to(Duration::from_millis(1000));
+        assert_that!(seen.windows(2).all(|pair| pair[1] <= pair[0])).is_true();
+    }
+}
+
+mod test_panics {
+    use super::*;
+    use std::time::Duration;
+
+    use doots::game::board::{Board, Edge};
+    use doots::game::engine::play_seated;
+    use doots::players::player::{Player, SeatedPlayer};
+
+    /* Plays the first free edge until its turn comes, then panics. */
+    struct Grumpy {
+        patience: usize,
+    }
+
+    impl Player for Grumpy {
+        fn name(&self) -> String {
+            "Grumpy".to_string()
+        }
+
+        fn play(&self, board: Board) -> Edge {
+            if self.patience <= board.iter_owned_edges().count() {
+                panic!("I HATE {}!", "MONDAYS");
+            }
+            board.iter_edges().find(|&e| board.is_free(e)).unwrap()
+        }
+    }
+
+    fn seated(patience: usize) -> Vec<SeatedPlayer> {
+        vec![
+            (
+                PlayerId::One,
+                Box::new(doots::ai::boox::AI::new(PlayerId::One)),
+            ),
+            (PlayerId::Two, Box::new(Grumpy { patience })),
+        ]
+    }
+
+    fn assert_grumpy_forfeits(opts: &Opts) {
+        let outcome = play_seated(opts, seated(3), &mut ()).expect("Game failed");
+
+        assert_that!(outcome.forfeit.map(|f| f.reason)).is_equal_to(Some(GameError::Panicked {
+            player_id: PlayerId::Two,
+            name: "Grumpy".to_string(),
+            message: "I HATE MONDAYS!".to_string(),
+        }));
+        assert_that!(outcome.board.iter_owned_edges().count() >= 3).is_true();
+        assert_that!(outcome.result).is_equal_to(WinnerResult::Winner(
+            PlayerId::One,
+            outcome.board.owned_boxes_count(PlayerId::One),
+        ));
+    }
+
+    #[test]
+    fn forfeits_a_panicking_player() {
+        assert_grumpy_forfeits(&Opts {
+            rows: 2,
+            cols: 2,
+            ..Opts::default()
+        });
+    }
+
+    #[test]
+    fn forfeits_a_panicking_player_on_the_clock() {
+        assert_grumpy_forfeits(&Opts {
+            rows: 2,
+            cols: 2,
+            move_time: Some(Duration::from_secs(5)),
+            ..Opts::default()
+        });
+    }
+
+    #[test]
+    fn keeps_playing_other_games() {
+        let opts = Opts {
+            rows: 2,
+            cols: 2,
+            ..Opts::default()
+        };
+        play_seated(&opts, seated(0), &mut ()).expect("Game failed");
+        let outcome = play_seated(&opts, seated(100), &mut ()).expect("Game failed");
+
+        assert_that!(outcome.forfeit).is_equal_to(None);
+        assert_that!(outcome.board.is_full()).is_true();
+    }
+}
+
+mod test_turn_context {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use doots::game::board::{Board, Edge, OwnedEdge};
+    use doots::game::engine::play_seated;
+    use doots::players::player::{Player, SeatedPlayer, TurnContext};
+
+    /* Plays like boox, but keeps every context it was handed. */
+    struct Spy {
+        boox: doots::ai::boox::AI,
+        contexts: Arc<Mutex<Vec<TurnContext>>>,
+    }
+
+    impl Player for Spy {
+        fn name(&self) -> String {
+            "Spy".to_string()
+        }
+
+        fn play(&self, board: Board) -> Edge {
+            self.boox.play(board)
+        }
+
+        fn play_turn(&self, context: TurnContext) -> Edge {
+            self.contexts.lock().unwrap().push(context.clone());
+            self.play(context.board)
+        }
+    }
+
+    fn spy_game() -> (Vec<OwnedEdge>, Vec<TurnContext>) {
+        let contexts = Arc::new(Mutex::new(vec![]));
+        let players: Vec<SeatedPlayer> = vec![
+            (
+                PlayerId::One,
+                Box::new(Spy {
+                    boox: doots::ai::boox::AI::new(PlayerId::One),
+                    contexts: Arc::clone(&contexts),
+                }),
+            ),
+            (
+                PlayerId::Two,
+                Box::new(doots::ai::doot::AI::new(PlayerId::Two)),
+            ),
+        ];
+        let opts = Opts {
+            rows: 2,
+            cols: 2,
+            turn_order: vec![PlayerId::Two, PlayerId::One],
+            ..Opts::default()
+        };
+        let outcome = play_seated(&opts, players, &mut ()).expect("Game failed");
+        let seen = contexts.lock().unwrap().clone();
+        (outcome.board.iter_owned_edges().collect(), seen)
+    }
+
+    #[test]
+    fn describes_the_turn() {
+        let (owned_edges, contexts) = spy_game();
+
+        assert_that!(contexts.is_empty()).is_false();
+        for context in contexts.iter() {
+            assert_that!(context.player_id).is_equal_to(PlayerId::One);
+            assert_that!(context.board.iter_owned_edges().count()).is_equal_to(context.turn);
+            assert_that!(owned_edges[context.turn].0).is_equal_to(PlayerId::One);
+            assert_that!(context.time_left).is_equal_to(None);
+            assert_that!(context.opponents())
+                .is_equal_to(vec![(PlayerId::Two, "Doot Two".to_string())]);
+        }
+        // Player Two goes first.
+        assert_that!(contexts[0].turn).is_equal_to(1);
+    }
+
+    #[test]
+    fn keeps_score() {
+        let (owned_edges, contexts) = spy_game();
+
+        for context in contexts.iter() {
+            let mut board = Board::new(2);
+            board
+                .draw_many(owned_edges[..context.turn].to_vec())
+                .expect("Draw failed");
+            assert_that!(context.score(PlayerId::One))
+                .is_equal_to(board.owned_boxes_count(PlayerId::One));
+            assert_that!(context.score(PlayerId::Two))
+                .is_equal_to(board.owned_boxes_count(PlayerId::Two));
+
+            let previous = owned_edges[..context.turn].last();
+            let mid_streak = previous.is_some_and(|&(id, edge)| {
+                id == PlayerId::One && {
+                    let mut before = board.clone();
+                    before.undo();
+                    before.would_claim_box(edge)
+                }
+            });
+            assert_that!(context.streak > 0).is_equal_to(mid_streak);
+        }
+    }
+}
+
+#[test]
+fn winner_from_counts() {
+    assert_that!(WinnerResult::from_counts(&[
+        (PlayerId::One, 2),
+        (PlayerId::Two, 3)
+    ]))
+    .is_equal_to(WinnerResult::Winner(PlayerId::Two, 3));
+    assert_that!(WinnerResult::from_counts(&[
+        (PlayerId::One, 3),
+        (PlayerId::Two, 1),
+        (PlayerId::Three, 3)
+    ]))
+    .is_equal_to(WinnerResult::Tie(vec![PlayerId::Three, PlayerId::One], 3));
+    assert_that!(WinnerResult::from_counts(&[])).is_equal_to(WinnerResult::None);
+}
+
+#[test]
+fn invalid_move_errors() {
+    let error = GameError::InvalidMove {
+        player_id: PlayerId::Two,
+        name: "Boox Two".to_string(),
+        edge: edge((0, 0), (0, 1)),
+        error: DrawError::AlreadyDrawn(edge((0, 0), (0, 1))),
+    };
+
+    assert_that!(error.to_string()).is_equal_to(format!(
+        "Player Two (Boox Two) attempted to draw an invalid edge: {}",
+        edge((0, 0), (0, 1))
+    ));
+    assert_that!(error.source().map(|source| source.to_string())).is_equal_to(Some(format!(
+        "Cannot redraw edge: {}",
+        edge((0, 0), (0, 1))
+    )));
+}
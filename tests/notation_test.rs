@@ -0,0 +1,98 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::board::{dot, edge, Board, OwnedEdge};
+use doots::game::notation::{format_edge, parse_dimensions, parse_edge, parse_edge_on};
+use doots::players::player::PlayerId;
+
+fn sample_board() -> Board {
+    let mut board = Board::with_dimensions(2, 3);
+    board
+        .draw_many(vec![
+            (PlayerId::One, edge((0, 0), (0, 1))),
+            (PlayerId::Two, edge((1, 0), (0, 0))),
+            (PlayerId::One, edge((1, 0), (1, 1))),
+            (PlayerId::Two, edge((1, 1), (0, 1))),
+            (PlayerId::Two, edge((2, 3), (1, 3))),
+        ])
+        .expect("Draw failed");
+    board
+}
+
+#[test]
+fn edges() {
+    assert_that!(format_edge(edge((3, 5), (3, 4)))).is_equal_to("3,4h".to_string());
+    assert_that!(format_edge(edge((3, 4), (4, 4)))).is_equal_to("3,4v".to_string());
+    assert_that!(parse_edge("3,4v")).is_equal_to(Ok(edge((3, 4), (4, 4))));
+    assert_that!(parse_edge("3,4").is_err()).is_true();
+    assert_that!(parse_edge("3,4é").is_err()).is_true();
+    assert_that!(parse_edge("").is_err()).is_true();
+    assert_that!(parse_edge("65535,0v").is_err()).is_true();
+    assert_that!(parse_edge("0,65535h").is_err()).is_true();
+}
+
+#[test]
+fn edges_on_a_board() {
+    assert_that!(parse_edge_on("1,0h", 1, 1)).is_equal_to(Ok(edge((1, 0), (1, 1))));
+    assert_that!(parse_edge_on("0,1v", 1, 1)).is_equal_to(Ok(edge((0, 1), (1, 1))));
+    assert_that!(parse_edge_on("0,1h", 1, 1).is_err()).is_true();
+    assert_that!(parse_edge_on("1,0v", 1, 1).is_err()).is_true();
+    assert_that!(parse_edge_on("65535,0v", 1, 1).is_err()).is_true();
+}
+
+#[test]
+fn dimensions() {
+    assert_that!(parse_dimensions("3x5")).is_equal_to(Ok((3, 5)));
+    assert_that!(parse_dimensions("100x1")).is_equal_to(Ok((100, 1)));
+    assert_that!(parse_dimensions("0x0").is_err()).is_true();
+    assert_that!(parse_dimensions("3x0").is_err()).is_true();
+    assert_that!(parse_dimensions("300x300").is_err()).is_true();
+}
+
+#[test]
+fn display() {
+    assert_that!(format!("{}", sample_board()))
+        .is_equal_to("2x3 p1,2 1:0,0h 2:0,0v 1:1,0h 2:0,1v 2:1,3v".to_string());
+}
+
+#[test]
+fn round_trip() {
+    let board = sample_board();
+    let parsed: Board = format!("{}", board).parse().expect("Parse failed");
+
+    assert_that!(parsed == board).is_true();
+    assert_that!(parsed.iter_owned_edges().collect::<Vec<OwnedEdge>>())
+        .is_equal_to(board.iter_owned_edges().collect::<Vec<OwnedEdge>>());
+    assert_that!(parsed.box_owner(dot(0, 0))).is_equal_to(Some(PlayerId::Two));
+    assert_that!(format!("{}", parsed)).is_equal_to(format!("{}", board));
+    assert_that!(board.to_string().parse::<Board>()).is_equal_to(Ok(board));
+}
+
+#[test]
+fn parse_with_comments_and_more_players() {
+    let board: Board = "
+        # A fixture with three players
+        1x2 p1,2,3
+        3:0,0h 1:0,1h
+    "
+    .parse()
+    .expect("Parse failed");
+
+    assert_that!(board.player_ids().to_vec()).is_equal_to(vec![
+        PlayerId::One,
+        PlayerId::Two,
+        PlayerId::Three,
+    ]);
+    assert_that!(board.edge_owner(edge((0, 0), (0, 1)))).is_equal_to(Some(PlayerId::Three));
+}
+
+#[test]
+fn parse_errors() {
+    assert_that!("".parse::<Board>().is_err()).is_true();
+    assert_that!("2by2".parse::<Board>().is_err()).is_true();
+    assert_that!("1x1 p1,2 3:0,0h".parse::<Board>().is_err()).is_true();
+    assert_that!("1x1 p1,2 1:0,0h 2:0,0h".parse::<Board>().is_err()).is_true();
+    assert_that!("1x1 p1,2 1:5,5h".parse::<Board>().is_err()).is_true();
+    assert_that!("1x1 1:65535,0v".parse::<Board>().is_err()).is_true();
+    assert_that!("300x300".parse::<Board>().is_err()).is_true();
+}
@@ -0,0 +1,97 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::board::{Board, DotBox, OwnedEdge, WinnerResult};
+use doots::game::engine::{run_game_with, Opts};
+use doots::game::observer::{GameObserver, RosterEntry};
+use doots::game::record::GameRecorder;
+use doots::players::player::PlayerId;
+
+#[derive(Default)]
+struct Tally {
+    players: Vec<RosterEntry>,
+    turns: usize,
+    edges: Vec<OwnedEdge>,
+    boxes: usize,
+    streaks: usize,
+    longest_streak: usize,
+    result: Option<WinnerResult>,
+}
+
+impl GameObserver for Tally {
+    fn game_start(&mut self, _board: &Board, players: &[RosterEntry]) {
+        self.players = players.to_vec();
+    }
+
+    fn turn_start(&mut self, board: &Board, turn: usize, _player_id: PlayerId, _streak: usize) {
+        assert_that!(board.iter_owned_edges().count()).is_equal_to(turn);
+        self.turns += 1;
+    }
+
+    fn edge_drawn(&mut self, _board: &Board, owned_edge: OwnedEdge) {
+        self.edges.push(owned_edge);
+    }
+
+    fn box_completed(&mut self, board: &Board, player_id: PlayerId, dotbox: DotBox) {
+        assert_that!(board.box_owner(dotbox.0)).is_equal_to(Some(player_id));
+        self.boxes += 1;
+    }
+
+    fn streak(&mut self, _board: &Board, _player_id: PlayerId, streak: usize) {
+        self.streaks += 1;
+        self.longest_streak = self.longest_streak.max(streak);
+    }
+
+    fn game_over(&mut self, _board: &Board, result: &WinnerResult) {
+        self.result = Some(result.clone());
+    }
+}
+
+fn opts() -> Opts {
+    Opts {
+        rows: 2,
+        cols: 3,
+        players: vec!["doot".to_string(), "boox".to_string()],
+        turn_order: vec![PlayerId::Two, PlayerId::One],
+        quiet: true,
+        ..Opts::default()
+    }
+}
+
+#[test]
+fn sees_every_event() {
+    let mut tally = Tally::default();
+    run_game_with(&opts(), &mut tally).expect("Game failed");
+
+    assert_that!(tally.players.clone()).is_equal_to(vec![
+        (PlayerId::Two, "Boox Two".to_string()),
+        (PlayerId::One, "Doot One".to_string()),
+    ]);
+    assert_that!(tally.turns).is_equal_to(17);
+    assert_that!(tally.edges.len()).is_equal_to(17);
+    assert_that!(tally.edges[0].0).is_equal_to(PlayerId::Two);
+    assert_that!(tally.boxes).is_equal_to(6);
+    assert_that!(tally.streaks <= 6).is_true();
+    assert_that!(tally.longest_streak <= tally.streaks).is_true();
+    assert_that!(tally.result.is_some()).is_true();
+}
+
+#[test]
+fn notifies_every_observer() {
+    let mut tally = Tally::default();
+    let mut recorder = GameRecorder::new(opts().players);
+    let mut observers: Vec<&mut dyn GameObserver> = vec![&mut tally, &mut recorder];
+    run_game_with(&opts(), &mut observers).expect("Game failed");
+
+    let record = recorder.record().expect("No record");
+    assert_that!(record.turn_order.clone()).is_equal_to(vec![PlayerId::Two, PlayerId::One]);
+    assert_that!(record.players[1].kind.clone()).is_equal_to("boox".to_string());
+    assert_that!(record
+        .moves
+        .iter()
+        .map(|m| m.owned_edge)
+        .collect::<Vec<_>>())
+    .is_equal_to(tally.edges.clone());
+    assert_that!(record.result.clone()).is_equal_to(tally.result.clone());
+    assert_that!(record.validate()).is_equal_to(Ok(()));
+}
@@ -0,0 +1,78 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::analysis::{safe_edges, valence};
+use doots::game::board::{Board, OwnedEdge};
+use doots::game::engine::{play_headless, Opts};
+use doots::game::opening::random_opening;
+use doots::players::player::PlayerId;
+
+const TWO: [PlayerId; 2] = [PlayerId::One, PlayerId::Two];
+
+fn opened(moves: usize, seed: u64) -> Vec<OwnedEdge> {
+    let mut board = Board::new(4);
+    random_opening(&mut board, &TWO, 0, moves, seed);
+    board.iter_owned_edges().collect()
+}
+
+#[test]
+fn is_repeatable() {
+    assert_that!(opened(10, 7)).is_equal_to(opened(10, 7));
+    assert_that!(opened(10, 7) == opened(10, 8)).is_false();
+}
+
+#[test]
+fn only_draws_safe_edges() {
+    let mut board = Board::new(4);
+    let drawn = random_opening(&mut board, &TWO, 0, 10, 3);
+
+    assert_that!(drawn).is_equal_to(10);
+    assert_that!(board
+        .iter_boxes()
+        .all(|dotbox| 2 <= valence(&board, dotbox)))
+    .is_true();
+}
+
+#[test]
+fn takes_turns_from_the_first_player() {
+    let mut board = Board::new(4);
+    random_opening(&mut board, &[PlayerId::Three, PlayerId::One], 1, 4, 3);
+    let owners: Vec<PlayerId> = board.iter_owned_edges().map(|(id, _)| id).collect();
+
+    assert_that!(owners).is_equal_to(vec![
+        PlayerId::One,
+        PlayerId::Three,
+        PlayerId::One,
+        PlayerId::Three,
+    ]);
+}
+
+#[test]
+fn stops_when_nothing_is_safe() {
+    let mut board = Board::new(2);
+    let drawn = random_opening(&mut board, &TWO, 0, 100, 5);
+
+    assert_that!(drawn < 100).is_true();
+    assert_that!(drawn).is_equal_to(board.iter_owned_edges().count());
+    assert_that!(safe_edges(&board).is_empty()).is_true();
+}
+
+#[test]
+fn varies_games_between_bots() {
+    let play = |seed| {
+        let outcome = play_headless(&Opts {
+            rows: 3,
+            cols: 3,
+            players: vec!["boox".to_string(), "boox".to_string()],
+            quiet: true,
+            random_opening: 4,
+            seed,
+            ..Opts::default()
+        })
+        .expect("Game failed");
+        outcome.board.iter_owned_edges().collect::<Vec<_>>()
+    };
+
+    assert_that!(play(1)).is_equal_to(play(1));
+    assert_that!(play(1) == play(2)).is_false();
+}
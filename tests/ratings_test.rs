@@ -0,0 +1,246 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+use spectral::numeric::OrderedAssertions;
+
+use doots::game::batch::play_batch;
+use doots::game::engine::{play_headless, run_game, Opts};
+use doots::game::ratings::{
+    outcome_scores, Rating, Ratings, INITIAL_DEVIATION, INITIAL_RATING, MIN_DEVIATION,
+};
+use doots::game::tournament::{run_tournament, TournamentOpts};
+
+fn scores(results: &[(&str, i64)]) -> Vec<(String, i64)> {
+    results
+        .iter()
+        .map(|&(player, score)| (player.to_string(), score))
+        .collect()
+}
+
+fn opts(players: &[&str]) -> Opts {
+    Opts {
+        rows: 3,
+        cols: 3,
+        players: players.iter().map(|p| p.to_string()).collect(),
+        quiet: true,
+        ..Opts::default()
+    }
+}
+
+mod test_glicko {
+    use super::*;
+
+    #[test]
+    fn starts_everyone_the_same() {
+        let rating = Ratings::new().get("doot");
+
+        assert_that!(rating).is_equal_to(Rating::default());
+        assert_that!(rating.rating).is_equal_to(INITIAL_RATING);
+        assert_that!(rating.interval()).is_equal_to((
+            INITIAL_RATING - 1.96 * INITIAL_DEVIATION,
+            INITIAL_RATING + 1.96 * INITIAL_DEVIATION,
+        ));
+    }
+
+    #[test]
+    fn moves_the_winner_up_and_the_loser_down() {
+        let mut ratings = Ratings::new();
+        ratings.record_game(&scores(&[("doot", 5), ("boox", 4)]));
+        let doot = ratings.get("doot");
+        let boox = ratings.get("boox");
+
+        // Worked out by hand from the Glicko formulas.
+        assert_that!((doot.rating - 1662.2).abs()).is_less_than(0.1);
+        assert_that!((doot.deviation - 290.2).abs()).is_less_than(0.1);
+        assert_that!(doot.rating - INITIAL_RATING).is_equal_to(INITIAL_RATING - boox.rating);
+        assert_that!(doot.games).is_equal_to(1);
+        assert_that!(doot.expected_score(&boox)).is_greater_than(0.5);
+    }
+
+    #[test]
+    fn leaves_even_ties_alone() {
+        let mut ratings = Ratings::new();
+        ratings.record_game(&scores(&[("doot", 2), ("boox", 2)]));
+
+        assert_that!(ratings.get("doot").rating).is_equal_to(INITIAL_RATING);
+        assert_that!(ratings.get("doot").deviation).is_less_than(INITIAL_DEVIATION);
+    }
+
+    #[test]
+    fn skips_self_play() {
+        let mut ratings = Ratings::new();
+        ratings.record_game(&scores(&[("boox", 5), ("boox", 4)]));
+
+        assert_that!(ratings).is_equal_to(Ratings::new());
+    }
+
+    #[test]
+    fn rates_a_player_in_two_seats_once() {
+        let mut ratings = Ratings::new();
+        ratings.record_game(&scores(&[("boox", 3), ("doot", 5), ("boox", 1)]));
+        let mut two_games = Ratings::new();
+        two_games.record_game(&scores(&[("doot", 5), ("boox", 3)]));
+        two_games.record_game(&scores(&[("doot", 5), ("boox", 1)]));
+
+        assert_that!(ratings.get("boox").games).is_equal_to(1);
+        assert_that!(ratings.get("doot").games).is_equal_to(1);
+        assert_that!(ratings.get("boox").rating).is_less_than(INITIAL_RATING);
+        // Both results count, but against the ratings from before the game.
+        assert_that!(ratings.get("boox").rating).is_less_than(two_games.get("boox").rating);
+    }
+
+    #[test]
+    fn gets_more_certain_with_games() {
+        let mut ratings = Ratings::new();
+        for _ in 0..2000 {
+            ratings.record_game(&scores(&[("doot", 1), ("boox", 0), ("sleepy", -1)]));
+        }
+        let ranked: Vec<&String> = ratings.ranked().into_iter().map(|(p, _)| p).collect();
+
+        assert_that!(ranked).is_equal_to(vec![
+            &"doot".to_string(),
+            &"boox".to_string(),
+            &"sleepy".to_string(),
+        ]);
+        assert_that!(ratings.get("boox").deviation).is_equal_to(MIN_DEVIATION);
+    }
+}
+
+mod test_file {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        let mut ratings = Ratings::new();
+        ratings.record_game(&scores(&[("doot", 5), ("boox", 4)]));
+        let text = ratings.to_string();
+
+        assert_that!(text.clone()).is_equal_to(
+            "# player rating deviation games\nboox 1337.8 290.2 1\ndoot 1662.2 290.2 1\n"
+                .to_string(),
+        );
+        let parsed = text.parse::<Ratings>().expect("Parse failed");
+        assert_that!(parsed.to_string()).is_equal_to(text);
+    }
+
+    #[test]
+    fn rejects_nonsense() {
+        for text in [
+            "doot 1500",
+            "doot x 350 0",
+            "doot 1500 0 0",
+            "doot 1500 350 -1",
+        ]
+        .iter()
+        {
+            assert_that!(text.parse::<Ratings>().is_err()).is_true();
+        }
+    }
+
+    #[test]
+    fn starts_missing_files_empty() {
+        let path = std::env::temp_dir().join("doots_ratings_missing.txt");
+        let _ = std::fs::remove_file(&path);
+
+        assert_that!(Ratings::load(&path).expect("Load failed")).is_equal_to(Ratings::new());
+    }
+}
+
+mod test_games {
+    use super::*;
+
+    #[test]
+    fn only_rates_ai_games() {
+        let outcome = play_headless(&opts(&["doot", "boox"])).expect("Game failed");
+        let kinds = vec!["doot".to_string(), "Sam".to_string()];
+
+        assert_that!(outcome_scores(&kinds, &outcome)).is_equal_to(None);
+    }
+
+    #[test]
+    fn updates_the_file_after_a_game() {
+        let path = std::env::temp_dir().join("doots_ratings_game.txt");
+        let _ = std::fs::remove_file(&path);
+        run_game(&Opts {
+            ratings: Some(path.clone()),
+            ..opts(&["doot", "boox"])
+        })
+        .expect("Game failed");
+
+        let ratings = Ratings::load(&path).expect("Load failed");
+        assert_that!(ratings.get("doot").games).is_equal_to(1);
+        assert_that!(ratings.get("doot").rating).is_greater_than(ratings.get("boox").rating);
+    }
+
+    #[test]
+    fn updates_the_file_after_a_headless_game() {
+        let path = std::env::temp_dir().join("doots_ratings_headless.txt");
+        let _ = std::fs::remove_file(&path);
+        for _ in 0..2 {
+            play_headless(&Opts {
+                ratings: Some(path.clone()),
+                ..opts(&["doot", "boox"])
+            })
+            .expect("Game failed");
+        }
+
+        let ratings = Ratings::load(&path).expect("Load failed");
+        assert_that!(ratings.get("boox").games).is_equal_to(2);
+    }
+
+    #[test]
+    fn updates_the_file_once_per_batch_game() {
+        let path = std::env::temp_dir().join("doots_ratings_batch.txt");
+        let _ = std::fs::remove_file(&path);
+        play_batch(
+            &Opts {
+                ratings: Some(path.clone()),
+                random_opening: 2,
+                ..opts(&["doot", "boox"])
+            },
+            3,
+            2,
+        )
+        .expect("Batch failed");
+
+        let ratings = Ratings::load(&path).expect("Load failed");
+        assert_that!(ratings.get("doot").games).is_equal_to(3);
+    }
+
+    #[test]
+    fn leaves_self_play_unrated() {
+        let path = std::env::temp_dir().join("doots_ratings_self_play.txt");
+        let _ = std::fs::remove_file(&path);
+        play_batch(
+            &Opts {
+                ratings: Some(path.clone()),
+                random_opening: 4,
+                ..opts(&["boox", "boox"])
+            },
+            4,
+            2,
+        )
+        .expect("Batch failed");
+
+        let ratings = Ratings::load(&path).expect("Load failed");
+        assert_that!(ratings.get("boox")).is_equal_to(Rating::default());
+    }
+
+    #[test]
+    fn updates_the_file_after_every_tournament_game() {
+        let path = std::env::temp_dir().join("doots_ratings_tournament.txt");
+        let _ = std::fs::remove_file(&path);
+        run_tournament(&TournamentOpts {
+            players: vec!["doot".to_string(), "boox".to_string()],
+            sizes: vec![2],
+            games: 4,
+            random_opening: 2,
+            seed: 5,
+            ratings: Some(path.clone()),
+            jobs: 3,
+        })
+        .expect("Tournament failed");
+
+        let ratings = Ratings::load(&path).expect("Load failed");
+        assert_that!(ratings.get("boox").games).is_equal_to(4);
+    }
+}
@@ -0,0 +1,198 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::board::{edge, Board, WinnerResult};
+use doots::game::engine::{run_game, Opts};
+use doots::game::record::{GameRecord, RecordedMove, RecordedPlayer};
+use doots::players::player::PlayerId;
+
+const SAMPLE: &str = r#"[Game "Doots & Booxes"]
+[Date "2026.10.18"]
+[Size "1x2"]
+[Player1 "doot"]
+[Name1 "Doot One"]
+[Player2 "hoomin"]
+[Name2 "Sam \"The Hoomin\" Two"]
+[Order "2,1"]
+[Result "1"]
+
+1. 2:0,0h
+2. 1:1,0h
+3. 2:0,0v
+4. 1:0,1h
+5. 2:1,1h
+6. 1:0,1v
+7. 1:0,2v +1
+"#;
+
+fn sample_record() -> GameRecord {
+    SAMPLE.parse::<GameRecord>().expect("Parse failed")
+}
+
+mod test_parse {
+    use super::*;
+
+    #[test]
+    fn reads_tags() {
+        let record = sample_record();
+
+        assert_that!((record.rows, record.cols)).is_equal_to((1, 2));
+        assert_that!(record.turn_order.clone()).is_equal_to(vec![PlayerId::Two, PlayerId::One]);
+        assert_that!(record.players[1].clone()).is_equal_to(RecordedPlayer {
+            id: PlayerId::Two,
+            kind: "hoomin".to_string(),
+            name: "Sam \"The Hoomin\" Two".to_string(),
+        });
+        assert_that!(record.result.clone())
+            .is_equal_to(Some(WinnerResult::Winner(PlayerId::One, 2)));
+    }
+
+    #[test]
+    fn reads_moves() {
+        let record = sample_record();
+
+        assert_that!(record.ply_count()).is_equal_to(7);
+        assert_that!(record.moves[6]).is_equal_to(RecordedMove {
+            owned_edge: (PlayerId::One, edge((0, 2), (1, 2))),
+            streak: 1,
+        });
+    }
+
+    #[test]
+    fn round_trips() {
+        let record = sample_record();
+        assert_that!(record.to_string()).is_equal_to(SAMPLE.to_string());
+    }
+
+    #[test]
+    fn rebuilds_any_ply() {
+        let record = sample_record();
+
+        assert_that!(record.board_at(0).unwrap().iter_owned_edges().count()).is_equal_to(0);
+        let board = record.board_at(3).unwrap();
+        assert_that!(board.is_drawn(edge((0, 0), (1, 0)))).is_true();
+        assert_that!(board.is_drawn(edge((0, 1), (0, 2)))).is_false();
+        assert_that!(record.final_board().unwrap().is_full()).is_true();
+        assert_that!(record.board_at(8).is_err()).is_true();
+    }
+
+    #[test]
+    fn rejects_bad_records() {
+        let wrong_streak = SAMPLE.replace("1:0,2v +1", "1:0,2v");
+        assert_that!(wrong_streak.parse::<GameRecord>().is_err()).is_true();
+
+        let wrong_result = SAMPLE.replace("[Result \"1\"]", "[Result \"2\"]");
+        assert_that!(wrong_result.parse::<GameRecord>().is_err()).is_true();
+
+        let skipped_move = SAMPLE.replace("4. ", "5. ");
+        assert_that!(skipped_move.parse::<GameRecord>().is_err()).is_true();
+
+        let repeated_edge = SAMPLE.replace("1:1,0h", "1:0,0h");
+        assert_that!(repeated_edge.parse::<GameRecord>().is_err()).is_true();
+
+        let moved_twice = SAMPLE.replace("2. 1:1,0h", "2. 2:1,0h");
+        assert_that!(moved_twice.parse::<GameRecord>().is_err()).is_true();
+
+        let no_size = SAMPLE.replace("[Size \"1x2\"]\n", "");
+        assert_that!(no_size.parse::<GameRecord>().is_err()).is_true();
+    }
+
+    #[test]
+    fn counts_streaks_in_moves() {
+        // Move 9 finishes two boxes at once, and still only makes a streak of 1.
+        let double_box = r#"[Size "1x3"]
+[Player1 "doot"]
+[Player2 "boox"]
+[Result "1"]
+
+1. 1:0,0h
+2. 2:0,1h
+3. 1:0,2h
+4. 2:1,0h
+5. 1:1,1h
+6. 2:1,2h
+7. 1:0,0v
+8. 2:0,2v
+9. 1:0,1v
+10. 1:0,3v +1
+"#;
+        let record = double_box.parse::<GameRecord>().expect("Parse failed");
+        assert_that!(record.moves[9].streak).is_equal_to(1);
+        assert_that!(record
+            .final_board()
+            .unwrap()
+            .owned_boxes_count(PlayerId::One))
+        .is_equal_to(3);
+
+        let counting_boxes = double_box.replace("1:0,3v +1", "1:0,3v +2");
+        assert_that!(counting_boxes.parse::<GameRecord>().is_err()).is_true();
+    }
+
+    #[test]
+    fn reads_unfinished_games() {
+        let unfinished: String = SAMPLE
+            .replace("[Result \"1\"]", "[Result \"*\"]")
+            .lines()
+            .take(13)
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let record = unfinished.parse::<GameRecord>().expect("Parse failed");
+
+        assert_that!(record.result.clone()).is_equal_to(None);
+        assert_that!(record.ply_count()).is_equal_to(3);
+    }
+}
+
+mod test_engine {
+    use super::*;
+
+    #[test]
+    fn records_the_game() {
+        let path = std::env::temp_dir().join("doots_record_test.txt");
+        run_game(&Opts {
+            rows: 2,
+            cols: 3,
+            players: vec!["boox".to_string(), "doot".to_string()],
+            quiet: true,
+            record: Some(path.clone()),
+            ..Opts::default()
+        })
+        .expect("Game failed");
+
+        let text = std::fs::read_to_string(&path).expect("Record missing");
+        let record = text.parse::<GameRecord>().expect("Parse failed");
+        let board = record.final_board().unwrap();
+
+        assert_that!(record.ply_count()).is_equal_to(17);
+        assert_that!(record.players[0].name.clone()).is_equal_to("Boox One".to_string());
+        assert_that!(record.result.clone()).is_equal_to(Some(board.winner()));
+    }
+
+    #[test]
+    fn records_the_starting_position() {
+        let path = std::env::temp_dir().join("doots_record_position_test.txt");
+        let position = "2x2 1:0,0h 2:0,0v 1:1,0h".parse::<Board>().unwrap();
+        run_game(&Opts {
+            rows: 2,
+            cols: 2,
+            players: vec!["boox".to_string(), "doot".to_string()],
+            quiet: true,
+            record: Some(path.clone()),
+            position: Some(position.clone()),
+            to_move: Some(PlayerId::Two),
+            ..Opts::default()
+        })
+        .expect("Game failed");
+
+        let text = std::fs::read_to_string(&path).expect("Record missing");
+        let record = text.parse::<GameRecord>().expect("Parse failed");
+
+        assert_that!(text.contains("[Position \"1:0,0h 2:0,0v 1:1,0h\"]")).is_true();
+        assert_that!(record.setup.clone())
+            .is_equal_to(position.iter_owned_edges().collect::<Vec<_>>());
+        assert_that!(record.ply_count()).is_equal_to(9);
+        assert_that!(record.moves[0].owned_edge.0).is_equal_to(PlayerId::Two);
+        assert_that!(format!("{}", record.initial_board().unwrap()))
+            .is_equal_to(format!("{}", position));
+    }
+}
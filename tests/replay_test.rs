@@ -0,0 +1,111 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::board::edge;
+use doots::game::record::GameRecord;
+use doots::game::replay::{parse_command, Replay, ReplayCommand};
+
+const SAMPLE: &str = r#"[Size "1x2"]
+[Player1 "doot"]
+[Name1 "Doot One"]
+[Player2 "boox"]
+[Name2 "Boox Two"]
+[Result "1"]
+
+1. 2:0,0h
+2. 1:1,0h
+3. 2:0,0v
+4. 1:0,1h
+5. 2:1,1h
+6. 1:0,1v
+7. 1:0,2v +1
+"#;
+
+fn sample_replay() -> Replay {
+    Replay::new(SAMPLE.parse::<GameRecord>().expect("Parse failed")).expect("Replay failed")
+}
+
+#[test]
+fn commands() {
+    assert_that!(parse_command("")).is_equal_to(Some(ReplayCommand::Forward(1)));
+    assert_that!(parse_command("n 4")).is_equal_to(Some(ReplayCommand::Forward(4)));
+    assert_that!(parse_command("b")).is_equal_to(Some(ReplayCommand::Back(1)));
+    assert_that!(parse_command("12")).is_equal_to(Some(ReplayCommand::Jump(12)));
+    assert_that!(parse_command("e")).is_equal_to(Some(ReplayCommand::End));
+    assert_that!(parse_command("q")).is_equal_to(Some(ReplayCommand::Quit));
+    assert_that!(parse_command("b x")).is_equal_to(None);
+    assert_that!(parse_command("what")).is_equal_to(None);
+}
+
+#[test]
+fn steps_through_moves() {
+    let mut replay = sample_replay();
+    assert_that!(replay.ply()).is_equal_to(0);
+
+    replay.apply(ReplayCommand::Forward(3));
+    assert_that!(replay.ply()).is_equal_to(3);
+    assert_that!(replay.board().is_drawn(edge((0, 0), (1, 0)))).is_true();
+
+    replay.apply(ReplayCommand::Back(2));
+    assert_that!(replay.ply()).is_equal_to(1);
+    assert_that!(replay.board().is_drawn(edge((0, 0), (1, 0)))).is_false();
+
+    replay.apply(ReplayCommand::Back(5));
+    assert_that!(replay.ply()).is_equal_to(0);
+
+    replay.apply(ReplayCommand::Jump(99));
+    assert_that!(replay.is_over()).is_true();
+    assert_that!(replay.board().is_full()).is_true();
+
+    assert_that!(replay.apply(ReplayCommand::Quit)).is_false();
+}
+
+#[test]
+fn captions_match_the_engine() {
+    let mut replay = sample_replay();
+
+    replay.jump_to(2);
+    assert_that!(replay.caption())
+        .is_equal_to("Turn #2: Doot One\nPlayer One drew: (1, 0)·─·(1, 1)".to_string());
+
+    replay.jump_to(7);
+    assert_that!(replay.caption()).is_equal_to(
+        [
+            "Streak 1! Doot One",
+            "Player One drew: (0, 2)·─·(1, 2)",
+            "Player One finished a box!",
+        ]
+        .join("\n"),
+    );
+    assert_that!(replay
+        .render()
+        .contains("Player One (Doot One) wins with 2 boxes!"))
+    .is_true();
+}
+
+#[test]
+fn starts_after_the_position() {
+    let record = SAMPLE
+        .replace("[Result", "[Position \"2:0,0h 1:1,0h\"]\n[Result")
+        .replace("1. 2:0,0h\n2. 1:1,0h\n", "")
+        .replace("3. ", "1. ")
+        .replace("4. ", "2. ")
+        .replace("5. ", "3. ")
+        .replace("6. ", "4. ")
+        .replace("7. ", "5. ");
+    let mut replay =
+        Replay::new(record.parse::<GameRecord>().expect("Parse failed")).expect("Replay failed");
+
+    assert_that!(replay.ply()).is_equal_to(0);
+    assert_that!(replay.board().iter_owned_edges().count()).is_equal_to(2);
+
+    replay.apply(ReplayCommand::Start);
+    assert_that!(replay.board().iter_owned_edges().count()).is_equal_to(2);
+
+    replay.jump_to(1);
+    assert_that!(replay.caption())
+        .is_equal_to("Turn #3: Boox Two\nPlayer Two drew: (0, 0)·─·(1, 0)".to_string());
+    replay.apply(ReplayCommand::End);
+    assert_that!(replay.ply()).is_equal_to(5);
+    assert_that!(replay.is_over()).is_true();
+}
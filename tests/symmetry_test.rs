@@ -0,0 +1,107 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::board::{dot, dotbox, edge, Board};
+use doots::game::symmetry::Symmetry;
+use doots::players::player::PlayerId;
+
+fn corner_box_board() -> Board {
+    let mut board = Board::with_dimensions(2, 3);
+    board
+        .draw_many(vec![
+            (PlayerId::One, edge((0, 0), (0, 1))),
+            (PlayerId::Two, edge((0, 0), (1, 0))),
+            (PlayerId::One, edge((1, 0), (1, 1))),
+            (PlayerId::Two, edge((0, 1), (1, 1))),
+            (PlayerId::Two, edge((2, 2), (2, 3))),
+        ])
+        .expect("Draw failed");
+    board
+}
+
+mod test_dots {
+    use super::*;
+
+    #[test]
+    fn transpose_swaps_row_and_col() {
+        assert_that!(dot(2, 7).transpose()).is_equal_to(dot(7, 2));
+    }
+
+    #[test]
+    fn four_quarter_turns_are_identity() {
+        let mut d = dot(1, 3);
+        for _ in 0..4 {
+            d = Symmetry::Rotate90.transform_dot(d, 4, 4);
+        }
+        assert_that!(d).is_equal_to(dot(1, 3));
+    }
+
+    #[test]
+    fn inverse_undoes_the_transform() {
+        for &symmetry in Symmetry::ALL.iter() {
+            let (rows, cols) = symmetry.dimensions(2, 3);
+            let there = symmetry.transform_dot(dot(1, 2), 2, 3);
+            let back = symmetry.inverse().transform_dot(there, rows, cols);
+            assert_that!(back).is_equal_to(dot(1, 2));
+        }
+    }
+}
+
+mod test_boxes_and_edges {
+    use super::*;
+
+    #[test]
+    fn flip_cols_moves_box_to_the_other_side() {
+        assert_that!(Symmetry::FlipCols.transform_box(dotbox((0, 0)), 2, 3))
+            .is_equal_to(dotbox((0, 2)));
+        assert_that!(Symmetry::Rotate90.transform_box(dotbox((0, 0)), 2, 3))
+            .is_equal_to(dotbox((0, 1)));
+    }
+
+    #[test]
+    fn transformed_edges_start_upper_left() {
+        let transformed = Symmetry::Rotate180.transform_edge(edge((0, 0), (0, 1)), 2, 2);
+        assert_that!(transformed.0).is_equal_to(dot(2, 1));
+        assert_that!(transformed.1).is_equal_to(dot(2, 2));
+    }
+}
+
+mod test_boards {
+    use super::*;
+
+    #[test]
+    fn symmetries_for_dimensions() {
+        assert_that!(Symmetry::for_dimensions(3, 3).len()).is_equal_to(8);
+        assert_that!(Symmetry::for_dimensions(3, 5).len()).is_equal_to(4);
+    }
+
+    #[test]
+    fn transform_keeps_owners_and_order() {
+        let board = corner_box_board();
+        let flipped = board.transform(Symmetry::FlipCols);
+
+        assert_that!(flipped.box_owner(dot(0, 2))).is_equal_to(Some(PlayerId::Two));
+        assert_that!(flipped.iter_owned_edges().last())
+            .is_equal_to(Some((PlayerId::Two, edge((2, 0), (2, 1)))));
+    }
+
+    #[test]
+    fn quarter_turn_swaps_dimensions() {
+        let turned = corner_box_board().transform(Symmetry::Rotate90);
+        assert_that!((turned.rows(), turned.cols())).is_equal_to((3, 2));
+        assert_that!(turned.box_owner(dot(0, 1))).is_equal_to(Some(PlayerId::Two));
+    }
+
+    #[test]
+    fn symmetric_positions_share_a_canonical_board() {
+        let board = corner_box_board();
+        let canonical = board.canonical();
+
+        for symmetry in Symmetry::for_dimensions(2, 3) {
+            let equivalent = board.transform(symmetry);
+            assert_that!(equivalent.canonical() == canonical).is_true();
+            assert_that!(equivalent.canonical().zobrist()).is_equal_to(canonical.zobrist());
+        }
+        assert_that!(canonical.position_key() <= board.position_key()).is_true();
+    }
+}
@@ -0,0 +1,139 @@
+use spectral::assert_that;
+use spectral::boolean::BooleanAssertions;
+
+use doots::game::error::GameError;
+use doots::game::tournament::{
+    run_tournament, schedule, validate, PairingScore, Tournament, TournamentGame, TournamentOpts,
+};
+
+fn opts(players: &[&str]) -> TournamentOpts {
+    TournamentOpts {
+        players: players.iter().map(|p| p.to_string()).collect(),
+        sizes: vec![2, 3],
+        games: 2,
+        random_opening: 2,
+        seed: 11,
+        ratings: None,
+        jobs: 2,
+    }
+}
+
+fn game(players: [usize; 2], boxes: [usize; 2], winner: Option<usize>) -> TournamentGame {
+    TournamentGame {
+        players,
+        size: 2,
+        seed: 0,
+        boxes,
+        winner,
+        forfeit: false,
+    }
+}
+
+mod test_schedule {
+    use super::*;
+
+    #[test]
+    fn pairs_everyone_on_every_size() {
+        let games = schedule(&opts(&["doot", "boox", "sleepy"]));
+
+        assert_that!(games.len()).is_equal_to(3 * 2 * 2);
+        assert_that!(games[0].players).is_equal_to([0, 1]);
+        assert_that!(games[1].players).is_equal_to([1, 0]);
+        assert_that!(games[1].size).is_equal_to(2);
+        assert_that!(games[2].size).is_equal_to(3);
+        assert_that!(games[11].players).is_equal_to([2, 1]);
+    }
+
+    #[test]
+    fn gives_every_game_its_own_seed() {
+        let games = schedule(&opts(&["doot", "boox"]));
+        let again = schedule(&opts(&["doot", "boox"]));
+
+        assert_that!(games.clone()).is_equal_to(again);
+        assert_that!(games[0].seed == games[1].seed).is_false();
+    }
+
+    #[test]
+    fn rejects_bad_entries() {
+        for players in [&["doot"][..], &["doot", "doot"], &["doot", "Sam"]].iter() {
+            let error = validate(&opts(players)).err();
+            assert_that!(matches!(error, Some(GameError::Setup(_)))).is_true();
+        }
+        assert_that!(validate(&opts(&["doot", "boox"])).is_ok()).is_true();
+    }
+}
+
+mod test_results {
+    use super::*;
+
+    fn sample() -> Tournament {
+        Tournament {
+            players: vec!["doot".to_string(), "boox".to_string()],
+            games: vec![
+                game([0, 1], [3, 1], Some(0)),
+                game([1, 0], [2, 2], None),
+                game([1, 0], [4, 0], Some(0)),
+            ],
+        }
+    }
+
+    #[test]
+    fn scores_each_pairing() {
+        let tournament = sample();
+
+        assert_that!(tournament.score(0, 1)).is_equal_to(PairingScore {
+            games: 3,
+            wins: 1,
+            losses: 1,
+            ties: 1,
+            margin: -2,
+        });
+        assert_that!(tournament.score(1, 0).average_margin()).is_equal_to(2.0 / 3.0);
+        assert_that!(tournament.total(1)).is_equal_to(tournament.score(1, 0));
+    }
+
+    #[test]
+    fn prints_a_crosstable() {
+        assert_that!(sample().crosstable()).is_equal_to(
+            [
+                "Player         doot           boox           Total",
+                "doot           ·              1-1-1 -0.67    1-1-1 -0.67",
+                "boox           1-1-1 +0.67    ·              1-1-1 +0.67",
+            ]
+            .join("\n"),
+        );
+    }
+
+    #[test]
+    fn exports_csv_and_json() {
+        let tournament = sample();
+
+        assert_that!(tournament.to_csv()).is_equal_to(
+            [
+                "player,opponent,games,wins,losses,ties,average_margin",
+                "doot,boox,3,1,1,1,-0.67",
+                "boox,doot,3,1,1,1,0.67",
+                "",
+            ]
+            .join("\n"),
+        );
+        let json = tournament.to_json();
+        assert_that!(json.contains("\"players\": [\"doot\", \"boox\"]")).is_true();
+        assert_that!(json.contains("\"boxes\": [2, 2], \"winner\": null")).is_true();
+        assert_that!(json.contains("\"boxes\": [4, 0], \"winner\": \"boox\"")).is_true();
+    }
+}
+
+#[test]
+fn plays_every_game() {
+    let tournament = run_tournament(&opts(&["doot", "boox"])).expect("Tournament failed");
+    let score = tournament.score(0, 1);
+
+    assert_that!(tournament.games.len()).is_equal_to(4);
+    assert_that!(score.games).is_equal_to(4);
+    assert_that!(score.wins + score.losses + score.ties).is_equal_to(4);
+    for played in tournament.games.iter() {
+        assert_that!(played.boxes[0] + played.boxes[1])
+            .is_equal_to((played.size * played.size) as usize);
+    }
+}